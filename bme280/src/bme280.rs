@@ -32,8 +32,9 @@ const REGISTER_TEMPERATURE: u8 = 0xFA;
 const REGISTER_HUMIDITY: u8 = 0xFD;
 const REGISTER_HUMIDITY_CONTROL: u8 = 0xF2;
 const REGISTER_MEASUREMENT_CONTROL: u8 = 0xF4;
+const REGISTER_CONFIG: u8 = 0xF5;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum HumiditySampling {
     Skipped = 0b00000_000,
@@ -44,7 +45,32 @@ pub enum HumiditySampling {
     Sampling16 = 0b00000_101,
 }
 
-#[derive(Debug)]
+impl HumiditySampling {
+    pub fn from_factor(factor: u8) -> Option<HumiditySampling> {
+        match factor {
+            0 => Some(HumiditySampling::Skipped),
+            1 => Some(HumiditySampling::Sampling1),
+            2 => Some(HumiditySampling::Sampling2),
+            4 => Some(HumiditySampling::Sampling4),
+            8 => Some(HumiditySampling::Sampling8),
+            16 => Some(HumiditySampling::Sampling16),
+            _ => None,
+        }
+    }
+
+    pub fn factor(self) -> u8 {
+        match self {
+            HumiditySampling::Skipped => 0,
+            HumiditySampling::Sampling1 => 1,
+            HumiditySampling::Sampling2 => 2,
+            HumiditySampling::Sampling4 => 4,
+            HumiditySampling::Sampling8 => 8,
+            HumiditySampling::Sampling16 => 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum TemperatireSampling {
     Skipped = 0b000_000_00,
@@ -55,7 +81,32 @@ pub enum TemperatireSampling {
     Sampling16 = 0b101_000_00,
 }
 
-#[derive(Debug)]
+impl TemperatireSampling {
+    pub fn from_factor(factor: u8) -> Option<TemperatireSampling> {
+        match factor {
+            0 => Some(TemperatireSampling::Skipped),
+            1 => Some(TemperatireSampling::Sampling1),
+            2 => Some(TemperatireSampling::Sampling2),
+            4 => Some(TemperatireSampling::Sampling4),
+            8 => Some(TemperatireSampling::Sampling8),
+            16 => Some(TemperatireSampling::Sampling16),
+            _ => None,
+        }
+    }
+
+    pub fn factor(self) -> u8 {
+        match self {
+            TemperatireSampling::Skipped => 0,
+            TemperatireSampling::Sampling1 => 1,
+            TemperatireSampling::Sampling2 => 2,
+            TemperatireSampling::Sampling4 => 4,
+            TemperatireSampling::Sampling8 => 8,
+            TemperatireSampling::Sampling16 => 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum PressureSampling {
     Skipped = 0b000_000_00,
@@ -66,14 +117,85 @@ pub enum PressureSampling {
     Sampling16 = 0b000_101_00,
 }
 
-#[derive(Debug)]
+impl PressureSampling {
+    pub fn from_factor(factor: u8) -> Option<PressureSampling> {
+        match factor {
+            0 => Some(PressureSampling::Skipped),
+            1 => Some(PressureSampling::Sampling1),
+            2 => Some(PressureSampling::Sampling2),
+            4 => Some(PressureSampling::Sampling4),
+            8 => Some(PressureSampling::Sampling8),
+            16 => Some(PressureSampling::Sampling16),
+            _ => None,
+        }
+    }
+
+    pub fn factor(self) -> u8 {
+        match self {
+            PressureSampling::Skipped => 0,
+            PressureSampling::Sampling1 => 1,
+            PressureSampling::Sampling2 => 2,
+            PressureSampling::Sampling4 => 4,
+            PressureSampling::Sampling8 => 8,
+            PressureSampling::Sampling16 => 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum SensorMode {
     Sleep = 0b000000_00,
-    Foeced = 0b000000_01,
+    Forced = 0b000000_01,
     Normal = 0b000000_11,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum StandbyTime {
+    Standby0_5 = 0b000_000_00,
+    Standby62_5 = 0b001_000_00,
+    Standby125 = 0b010_000_00,
+    Standby250 = 0b011_000_00,
+    Standby500 = 0b100_000_00,
+    Standby1000 = 0b101_000_00,
+    Standby10 = 0b110_000_00,
+    Standby20 = 0b111_000_00,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Filter {
+    Off = 0b000_000_00,
+    Coefficient2 = 0b000_001_00,
+    Coefficient4 = 0b000_010_00,
+    Coefficient8 = 0b000_011_00,
+    Coefficient16 = 0b000_100_00,
+}
+
+impl Filter {
+    pub fn from_coefficient(coefficient: u8) -> Option<Filter> {
+        match coefficient {
+            0 => Some(Filter::Off),
+            2 => Some(Filter::Coefficient2),
+            4 => Some(Filter::Coefficient4),
+            8 => Some(Filter::Coefficient8),
+            16 => Some(Filter::Coefficient16),
+            _ => None,
+        }
+    }
+
+    pub fn coefficient(self) -> u8 {
+        match self {
+            Filter::Off => 0,
+            Filter::Coefficient2 => 2,
+            Filter::Coefficient4 => 4,
+            Filter::Coefficient8 => 8,
+            Filter::Coefficient16 => 16,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BmeStatus {
     measuring: bool,
@@ -137,6 +259,10 @@ impl Bme280 {
         })
     }
 
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
     pub fn id<I2C>(&self, i2c: &mut I2C) -> Result<u8, I2C::Error>
     where
         I2C: WriteRead,
@@ -166,8 +292,8 @@ impl Bme280 {
         i2c.write_read(self.address, &[REGISTER_STATUS], &mut buffer)?;
 
         Ok(BmeStatus::new(
-            buffer[0] & 0b0000_1000 == 0,
-            buffer[0] & 0b0000_0001 == 0,
+            buffer[0] & 0b0000_1000 != 0,
+            buffer[0] & 0b0000_0001 != 0,
         ))
     }
 
@@ -194,7 +320,23 @@ impl Bme280 {
         Ok(())
     }
 
-    pub fn temperature<I2C>(&mut self, i2c: &mut I2C) -> Result<i32, I2C::Error>
+    // Config register writes may be ignored in normal mode, should be called while sensor in sleep mode.
+    pub fn config<I2C>(
+        &self,
+        i2c: &mut I2C,
+        standby: StandbyTime,
+        filter: Filter,
+    ) -> Result<(), I2C::Error>
+    where
+        I2C: Write,
+    {
+        i2c.write(self.address, &[REGISTER_CONFIG, standby as u8 | filter as u8])?;
+
+        Ok(())
+    }
+
+    // Temperature in 0.01 C, `None` if measurement was skipped.
+    pub fn temperature<I2C>(&mut self, i2c: &mut I2C) -> Result<Option<i32>, I2C::Error>
     where
         I2C: WriteRead,
     {
@@ -205,7 +347,7 @@ impl Bme280 {
             ((buffer[0] as i32) << 12) | ((buffer[1] as i32) << 4) | ((buffer[2] as i32) >> 4);
 
         if adc_temperature == 0x080000 {
-            return Ok(0);
+            return Ok(None);
         }
 
         let dig_t1 = self.compensation_data.dig_t1 as i32;
@@ -222,10 +364,11 @@ impl Bme280 {
 
         let temperature = (self.temprerature_fine * 5 + 128) >> 8;
 
-        Ok(temperature)
+        Ok(Some(temperature))
     }
 
-    pub fn pressure<I2C>(&self, i2c: &mut I2C) -> Result<u32, I2C::Error>
+    // Pressure in Pa (Q24.8 fixed point), `None` if measurement was skipped.
+    pub fn pressure<I2C>(&self, i2c: &mut I2C) -> Result<Option<u32>, I2C::Error>
     where
         I2C: WriteRead,
     {
@@ -236,7 +379,7 @@ impl Bme280 {
             ((buffer[0] as i32) << 12) | ((buffer[1] as i32) << 4) | ((buffer[2] as i32) >> 4);
 
         if adc_pressure == 0x080000 {
-            return Ok(0);
+            return Ok(None);
         }
 
         let dig_p1 = self.compensation_data.dig_p1 as i64;
@@ -254,7 +397,7 @@ impl Bme280 {
         let var1 = ((1 << 47) + var1) * (dig_p1) >> 33;
 
         if var1 == 0 {
-            return Ok(0); // avoid exception caused by division by zero
+            return Ok(None); // avoid exception caused by division by zero
         }
 
         let dig_p9 = self.compensation_data.dig_p9 as i64;
@@ -267,10 +410,11 @@ impl Bme280 {
         let var2 = ((dig_p8) * pressure) >> 19;
         let pressure = ((pressure + var1 + var2) >> 8) + (dig_p7 << 4);
 
-        Ok(pressure as u32)
+        Ok(Some(pressure as u32))
     }
 
-    pub fn humidity<I2C>(&self, i2c: &mut I2C) -> Result<u32, I2C::Error>
+    // Humidity in % (Q22.10 fixed point), `None` if measurement was skipped.
+    pub fn humidity<I2C>(&self, i2c: &mut I2C) -> Result<Option<u32>, I2C::Error>
     where
         I2C: WriteRead,
     {
@@ -280,7 +424,7 @@ impl Bme280 {
         let adc_humidity = ((buffer[0] as i32) << 8) | (buffer[1] as i32);
 
         if adc_humidity == 0x8000 {
-            return Ok(0);
+            return Ok(None);
         }
 
         let dig_h1 = self.compensation_data.dig_h1 as i32;
@@ -307,7 +451,7 @@ impl Bme280 {
             v_x1_u32r
         };
 
-        Ok((v_x1_u32r >> 12) as u32)
+        Ok(Some((v_x1_u32r >> 12) as u32))
     }
 }
 
@@ -447,3 +591,63 @@ impl CompensationData {
         Ok((((buffer[0] & 0xf0) as i16) << 4) | (buffer[1] as i16))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns zero calibration data and given raw measurement.
+    struct MockI2C([u8; 3]);
+
+    impl WriteRead for MockI2C {
+        type Error = ();
+
+        fn write_read(&mut self, _address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+            match bytes[0] {
+                REGISTER_PRESSURE | REGISTER_TEMPERATURE | REGISTER_HUMIDITY => {
+                    buffer.copy_from_slice(&self.0[..buffer.len()])
+                }
+                _ => buffer.fill(0),
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn skipped_measurement() {
+        let mut i2c = MockI2C([0x80, 0x00, 0x00]);
+        let mut bme280 = Bme280::address_low(&mut i2c).unwrap();
+
+        assert_eq!(bme280.temperature(&mut i2c), Ok(None));
+        assert_eq!(bme280.pressure(&mut i2c), Ok(None));
+        assert_eq!(bme280.humidity(&mut i2c), Ok(None));
+
+        let mut i2c = MockI2C([0x7F, 0x00, 0x00]);
+
+        assert_eq!(bme280.temperature(&mut i2c), Ok(Some(0)));
+        assert_eq!(bme280.humidity(&mut i2c), Ok(Some(0)));
+    }
+
+    #[test]
+    fn sampling_factors() {
+        for factor in [0, 1, 2, 4, 8, 16] {
+            assert_eq!(HumiditySampling::from_factor(factor).unwrap().factor(), factor);
+            assert_eq!(TemperatireSampling::from_factor(factor).unwrap().factor(), factor);
+            assert_eq!(PressureSampling::from_factor(factor).unwrap().factor(), factor);
+        }
+
+        assert_eq!(HumiditySampling::from_factor(3), None);
+        assert_eq!(TemperatireSampling::from_factor(32), None);
+        assert_eq!(PressureSampling::from_factor(5), None);
+    }
+
+    #[test]
+    fn filter_coefficients() {
+        for coefficient in [0, 2, 4, 8, 16] {
+            assert_eq!(Filter::from_coefficient(coefficient).unwrap().coefficient(), coefficient);
+        }
+
+        assert_eq!(Filter::from_coefficient(1), None);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::math::ln;
+use crate::math::round;
+
+// Magnus formula coefficients by Sonntag (1990), error below 0.1 C from -45 C to 60 C.
+const B: f32 = 17.62;
+const C: f32 = 243.12;
+
+// Dew point in 0.01 C from temperature in 0.01 C and humidity in % (Q22.10 fixed point), `None` for zero humidity.
+pub fn dew_point(temperature: i32, humidity: u32) -> Option<i32> {
+    if humidity == 0 {
+        return None;
+    }
+
+    let temperature = temperature as f32 / 100.0;
+    let humidity = humidity as f32 / 1024.0;
+    let gamma = ln(humidity / 100.0) + B * temperature / (C + temperature);
+    let dew_point = C * gamma / (B - gamma);
+
+    Some(round(dew_point * 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dew_points() {
+        assert_eq!(dew_point(2_000, 50 * 1024), Some(926));
+        assert_eq!(dew_point(2_500, 100 * 1024), Some(2_500));
+        assert_eq!(dew_point(3_000, 70 * 1024), Some(2_393));
+        assert_eq!(dew_point(-1_000, 80 * 1024), Some(-1_280));
+        assert_eq!(dew_point(0, 5 * 1024), Some(-3_533));
+        assert_eq!(dew_point(2_000, 0), None);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::math::exp;
+use crate::math::ln;
+use crate::math::round;
+
+const STEADY_CHANGE: i32 = 10; // Pa, tendency is reported with 0.1 hPa resolution.
+const FORECAST_CHANGE: i32 = 160; // Pa per 3 hours, smaller change is steady pressure for forecast.
+
+// Standard atmosphere: pressure = sea level pressure * (1 - altitude / 44330 m) ^ 5.255.
+const ATMOSPHERE_HEIGHT: f32 = 44_330.0;
+const ATMOSPHERE_EXPONENT: f32 = 5.255;
+
+// Zambretti numbers 1 - 9 for falling, 10 - 19 for steady and 20 - 32 for rising pressure mapped to forecast letters.
+const FALLING: [u8; 9] = *b"ABDHORUVX";
+const STEADY: [u8; 10] = *b"ABEKNPSWXZ";
+const RISING: [u8; 13] = *b"ABCFGIJLMQTYZ";
+
+const DESCRIPTIONS: [&str; 26] = [
+    "Settled fine",
+    "Fine weather",
+    "Becoming fine",
+    "Fine, becoming less settled",
+    "Fine, possible showers",
+    "Fairly fine, improving",
+    "Fairly fine, possible showers early",
+    "Fairly fine, showery later",
+    "Showery early, improving",
+    "Changeable, mending",
+    "Fairly fine, showers likely",
+    "Rather unsettled, clearing later",
+    "Unsettled, probably improving",
+    "Showery, bright intervals",
+    "Showery, becoming less settled",
+    "Changeable, some rain",
+    "Unsettled, short fine intervals",
+    "Unsettled, rain later",
+    "Unsettled, some rain",
+    "Mostly very unsettled",
+    "Occasional rain, worsening",
+    "Rain at times, very unsettled",
+    "Rain at frequent intervals",
+    "Rain, very unsettled",
+    "Stormy, may improve",
+    "Stormy, much rain",
+];
+
+// Characteristic of pressure tendency during 3 hours, WMO code table 0200.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureTendency {
+    RisingThenFalling = 0,
+    RisingThenSteady = 1,
+    Rising = 2,
+    SteadyThenRising = 3,
+    Steady = 4,
+    FallingThenRising = 5,
+    FallingThenSteady = 6,
+    Falling = 7,
+    SteadyThenFalling = 8,
+}
+
+impl PressureTendency {
+    // Pressures in Pa measured 3 hours ago, 1.5 hours ago and now.
+    pub fn from_pressures(before: i32, middle: i32, now: i32) -> PressureTendency {
+        let change = now - before;
+        let first = middle - before;
+        let second = now - middle;
+
+        if change.abs() < STEADY_CHANGE {
+            if first >= STEADY_CHANGE && second <= -STEADY_CHANGE {
+                PressureTendency::RisingThenFalling
+            } else if first <= -STEADY_CHANGE && second >= STEADY_CHANGE {
+                PressureTendency::FallingThenRising
+            } else {
+                PressureTendency::Steady
+            }
+        } else if change > 0 {
+            if second <= -STEADY_CHANGE {
+                PressureTendency::RisingThenFalling
+            } else if first < STEADY_CHANGE || second >= first + STEADY_CHANGE {
+                PressureTendency::SteadyThenRising
+            } else if second <= first - STEADY_CHANGE {
+                PressureTendency::RisingThenSteady
+            } else {
+                PressureTendency::Rising
+            }
+        } else if second >= STEADY_CHANGE {
+            PressureTendency::FallingThenRising
+        } else if first > -STEADY_CHANGE || second <= first - STEADY_CHANGE {
+            PressureTendency::SteadyThenFalling
+        } else if second >= first + STEADY_CHANGE {
+            PressureTendency::FallingThenSteady
+        } else {
+            PressureTendency::Falling
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+// Forecast by Negretti and Zambra table from sea level pressure and its change, letter `A` is the best weather.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Forecast(u8);
+
+impl Forecast {
+    // Sea level pressure in Pa and its change in Pa during last 3 hours.
+    pub fn zambretti(pressure: u32, change: i32) -> Forecast {
+        let hectopascals = pressure as f32 / 100.0;
+
+        let letter = if change <= -FORECAST_CHANGE {
+            let number = round(127.0 - 0.12 * hectopascals).clamp(1, 9);
+            FALLING[number as usize - 1]
+        } else if change >= FORECAST_CHANGE {
+            let number = round(185.0 - 0.16 * hectopascals).clamp(20, 32);
+            RISING[number as usize - 20]
+        } else {
+            let number = round(144.0 - 0.13 * hectopascals).clamp(10, 19);
+            STEADY[number as usize - 10]
+        };
+
+        Forecast(letter - b'A')
+    }
+
+    pub fn letter(self) -> char {
+        (b'A' + self.0) as char
+    }
+
+    pub fn description(self) -> &'static str {
+        DESCRIPTIONS[self.0 as usize]
+    }
+}
+
+// Pressure reduced to sea level by standard atmosphere, pressure in Pa and altitude in metres.
+pub fn sea_level_pressure(pressure: u32, altitude: i32) -> u32 {
+    let ratio = 1.0 - altitude as f32 / ATMOSPHERE_HEIGHT;
+
+    round(pressure as f32 * exp(-ATMOSPHERE_EXPONENT * ln(ratio))) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tendencies() {
+        let values = [
+            (100_000, 100_005, 100_002, PressureTendency::Steady),
+            (100_000, 100_050, 100_005, PressureTendency::RisingThenFalling),
+            (100_000, 99_950, 99_995, PressureTendency::FallingThenRising),
+            (100_000, 100_080, 100_100, PressureTendency::RisingThenSteady),
+            (100_000, 100_050, 100_100, PressureTendency::Rising),
+            (100_000, 100_000, 100_100, PressureTendency::SteadyThenRising),
+            (100_000, 100_020, 100_100, PressureTendency::SteadyThenRising),
+            (100_000, 100_100, 100_050, PressureTendency::RisingThenFalling),
+            (100_000, 99_900, 99_950, PressureTendency::FallingThenRising),
+            (100_000, 99_920, 99_900, PressureTendency::FallingThenSteady),
+            (100_000, 99_950, 99_900, PressureTendency::Falling),
+            (100_000, 100_000, 99_900, PressureTendency::SteadyThenFalling),
+            (100_000, 99_980, 99_900, PressureTendency::SteadyThenFalling),
+        ];
+
+        for (before, middle, now, expected) in values {
+            let tendency = PressureTendency::from_pressures(before, middle, now);
+            assert_eq!(tendency, expected, "{} {} {}", before, middle, now);
+        }
+
+        assert_eq!(PressureTendency::Falling.code(), 7);
+    }
+
+    #[test]
+    fn forecasts() {
+        assert_eq!(Forecast::zambretti(101_300, 0).letter(), 'E');
+        assert_eq!(Forecast::zambretti(101_300, -200).letter(), 'O');
+        assert_eq!(Forecast::zambretti(101_300, 200).letter(), 'F');
+        assert_eq!(Forecast::zambretti(104_000, 0).letter(), 'A');
+        assert_eq!(Forecast::zambretti(96_000, -300).letter(), 'X');
+        assert_eq!(Forecast::zambretti(95_000, 300).letter(), 'Z');
+        assert_eq!(Forecast::zambretti(101_300, -200).description(), "Showery, becoming less settled");
+    }
+
+    #[test]
+    fn sea_level() {
+        assert_eq!(sea_level_pressure(101_325, 0), 101_325);
+        assert!((sea_level_pressure(89_875, 1_000) as i32 - 101_325).abs() < 20);
+        assert!((sea_level_pressure(102_532, -100) as i32 - 101_325).abs() < 20);
+    }
+}
@@ -1,16 +1,32 @@
 #![no_std]
 
 mod bme280;
+mod dew_point;
+mod forecast;
 mod humidity;
+mod math;
 mod pressure;
+mod smoothing;
+mod statistics;
 mod temperature;
 
 pub use crate::bme280::Bme280;
 pub use crate::bme280::BmeStatus;
+pub use crate::bme280::Filter;
 pub use crate::bme280::HumiditySampling;
 pub use crate::bme280::PressureSampling;
 pub use crate::bme280::SensorMode;
+pub use crate::bme280::StandbyTime;
 pub use crate::bme280::TemperatireSampling;
+pub use crate::dew_point::dew_point;
+pub use crate::forecast::sea_level_pressure;
+pub use crate::forecast::Forecast;
+pub use crate::forecast::PressureTendency;
 pub use crate::humidity::HumidityExt;
 pub use crate::pressure::PressureExt;
+pub use crate::smoothing::median;
+pub use crate::smoothing::RateLimit;
+pub use crate::smoothing::Smoothing;
+pub use crate::statistics::RollingStatistics;
+pub use crate::statistics::Summary;
 pub use crate::temperature::TemperatureExt;
@@ -0,0 +1,93 @@
+use core::f32::consts::LN_2;
+
+// Natural logarithm for positive values, `core` has no `f32::ln` without `std`. Value is split to 2^e * m with
+// m in [1, 2) and ln(m) is calculated by series 2 * atanh((m - 1) / (m + 1)), error is below 1e-6.
+pub(crate) fn ln(value: f32) -> f32 {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 23) & 0xFF) as i32 - 127;
+    let mantissa = f32::from_bits((bits & 0x007F_FFFF) | 0x3F80_0000);
+    let t = (mantissa - 1.0) / (mantissa + 1.0);
+    let t2 = t * t;
+    let series = t * (1.0 + t2 * (1.0 / 3.0 + t2 * (1.0 / 5.0 + t2 * (1.0 / 7.0 + t2 / 9.0))));
+
+    exponent as f32 * LN_2 + 2.0 * series
+}
+
+// Rounds half away from zero, `core` has no `f32::round` without `std`.
+pub(crate) fn round(value: f32) -> i32 {
+    if value < 0.0 {
+        (value - 0.5) as i32
+    } else {
+        (value + 0.5) as i32
+    }
+}
+
+// Exponent for arguments below 10 by absolute value, `core` has no `f32::exp` without `std`. Series is calculated for
+// x / 16 and squared 4 times, relative error is below 1e-6.
+pub(crate) fn exp(value: f32) -> f32 {
+    let x = value / 16.0;
+    let mut result = 1.0 + x * (1.0 + x / 2.0 * (1.0 + x / 3.0 * (1.0 + x / 4.0 * (1.0 + x / 5.0 * (1.0 + x / 6.0)))));
+
+    for _ in 0..4 {
+        result *= result;
+    }
+
+    result
+}
+
+// Square root by Newton's method from estimate made of halved exponent, `core` has no `f32::sqrt` without `std`.
+pub(crate) fn sqrt(value: f32) -> f32 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+
+    let mut root = f32::from_bits((value.to_bits() >> 1) + 0x1FBD_1DF5);
+
+    for _ in 0..4 {
+        root = (root + value / root) / 2.0;
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f32::consts::E;
+    use core::f32::consts::LN_10;
+    use core::f32::consts::SQRT_2;
+
+    #[test]
+    fn logarithm() {
+        let values = [
+            (0.05, -2.995_732),
+            (0.5, -LN_2),
+            (1.0, 0.0),
+            (2.0, LN_2),
+            (10.0, LN_10),
+            (1_000.0, 6.907_755),
+        ];
+
+        for (value, expected) in values {
+            assert!((ln(value) - expected).abs() < 1e-5, "ln({})", value);
+        }
+    }
+
+    #[test]
+    fn exponent() {
+        let values = [(-1.0, 0.367_879_4), (0.0, 1.0), (0.1, 1.105_171), (1.0, E), (5.0, 148.413_16)];
+
+        for (value, expected) in values {
+            assert!((exp(value) / expected - 1.0).abs() < 1e-5, "exp({})", value);
+        }
+    }
+
+    #[test]
+    fn square_root() {
+        let values = [(0.0, 0.0), (0.25, 0.5), (2.0, SQRT_2), (100.0, 10.0), (1.0e9, 31_622.777)];
+
+        for (value, expected) in values {
+            assert!((sqrt(value) - expected).abs() <= expected * 1e-6, "sqrt({})", value);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+const MAX_REJECTIONS: u8 = 3; // Consecutive rejected values, next one is accepted as real change.
+
+// Allowed change of value since last accepted one: `step` and `rate` for each second between values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub step: i32,
+    pub rate: i32,
+}
+
+impl RateLimit {
+    fn allows(&self, change: i32, elapsed: u32) -> bool {
+        let elapsed = elapsed.min(i32::MAX as u32) as i32;
+
+        change.unsigned_abs() <= self.step.saturating_add(self.rate.saturating_mul(elapsed)) as u32
+    }
+}
+
+// Spike rejection followed by exponential moving average of one channel. Value changing faster than physically
+// possible is rejected, but after `MAX_REJECTIONS` rejected values in a row next value is accepted and restarts
+// average, so real step change (sensor moved outdoors) is not rejected forever.
+pub struct Smoothing {
+    limit: RateLimit,
+    weight: u8, // Weight of new value in %, 100 disables averaging.
+    last: Option<(u32, i32)>,
+    average: Option<i32>,
+    rejections: u8,
+}
+
+impl Smoothing {
+    pub const fn new(limit: RateLimit, weight: u8) -> Smoothing {
+        Smoothing {
+            limit,
+            weight,
+            last: None,
+            average: None,
+            rejections: 0,
+        }
+    }
+
+    pub fn set_weight(&mut self, weight: u8) {
+        self.weight = weight;
+    }
+
+    // Time in seconds. Returns `false` if value is rejected, average is not changed then.
+    pub fn push(&mut self, time: u32, value: i32) -> bool {
+        if let Some((last_time, last_value)) = self.last {
+            let allowed = self.limit.allows(value.wrapping_sub(last_value), time.wrapping_sub(last_time));
+
+            if !allowed && self.rejections < MAX_REJECTIONS {
+                self.rejections += 1;
+                return false;
+            }
+
+            if !allowed {
+                self.average = None;
+            }
+        }
+
+        self.last = Some((time, value));
+        self.rejections = 0;
+        self.average = Some(match self.average {
+            Some(average) => average + ((value as i64 - average as i64) * self.weight as i64 / 100) as i32,
+            None => value,
+        });
+
+        true
+    }
+
+    // Averaged value, `None` until first value is accepted.
+    pub fn value(&self) -> Option<i32> {
+        self.average
+    }
+
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.average = None;
+        self.rejections = 0;
+    }
+}
+
+// Median of values, average of two middle values for even count. Values are sorted in place.
+pub fn median(values: &mut [i32]) -> Option<i32> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_unstable();
+
+    let middle = values.len() / 2;
+
+    if values.len() % 2 == 1 {
+        Some(values[middle])
+    } else {
+        Some(((values[middle - 1] as i64 + values[middle] as i64) / 2) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit { step: 100, rate: 10 };
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [5]), Some(5));
+        assert_eq!(median(&mut [3, -100, 2]), Some(2));
+        assert_eq!(median(&mut [10, 1, 4, 7]), Some(5));
+        assert_eq!(median(&mut [2_000, 2_010, 0, 2_005, 9_999]), Some(2_005));
+    }
+
+    #[test]
+    fn average() {
+        let mut smoothing = Smoothing::new(LIMIT, 50);
+
+        assert_eq!(smoothing.value(), None);
+        assert!(smoothing.push(0, 1_000));
+        assert_eq!(smoothing.value(), Some(1_000));
+        assert!(smoothing.push(1, 1_100));
+        assert_eq!(smoothing.value(), Some(1_050));
+        assert!(smoothing.push(2, 1_100));
+        assert_eq!(smoothing.value(), Some(1_075));
+
+        smoothing.set_weight(100);
+        assert!(smoothing.push(3, 1_000));
+        assert_eq!(smoothing.value(), Some(1_000));
+
+        smoothing.reset();
+        assert_eq!(smoothing.value(), None);
+    }
+
+    #[test]
+    fn spikes() {
+        let mut smoothing = Smoothing::new(LIMIT, 100);
+
+        assert!(smoothing.push(0, 1_000));
+        assert!(!smoothing.push(1, 1_200));
+        assert!(!smoothing.push(2, 0));
+        assert_eq!(smoothing.value(), Some(1_000));
+
+        // Allowed change grows with time since last accepted value.
+        assert!(smoothing.push(20, 1_250));
+        assert_eq!(smoothing.value(), Some(1_250));
+    }
+
+    #[test]
+    fn step_change() {
+        let mut smoothing = Smoothing::new(LIMIT, 50);
+
+        assert!(smoothing.push(0, 1_000));
+
+        for time in 1..=3 {
+            assert!(!smoothing.push(time, 5_000));
+        }
+
+        assert!(smoothing.push(4, 5_000));
+        assert_eq!(smoothing.value(), Some(5_000));
+        assert!(smoothing.push(5, 5_050));
+        assert_eq!(smoothing.value(), Some(5_025));
+    }
+}
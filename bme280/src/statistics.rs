@@ -0,0 +1,208 @@
+use crate::math::round;
+use crate::math::sqrt;
+
+// Minimum, maximum, mean and population standard deviation in units of pushed values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub count: u32,
+    pub min: i32,
+    pub max: i32,
+    pub mean: i32,
+    pub deviation: i32,
+}
+
+// Values of one channel in one bucket, mean and sum of squared differences from mean are updated by Welford's method
+// and buckets are merged by parallel algorithm of Chan et al. Precision of `f32` is enough for values up to 2^20 (for
+// example pressure in Pa).
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    count: u32,
+    min: i32,
+    max: i32,
+    mean: f32,
+    squares: f32,
+}
+
+impl Accumulator {
+    const EMPTY: Accumulator = Accumulator {
+        count: 0,
+        min: i32::MAX,
+        max: i32::MIN,
+        mean: 0.0,
+        squares: 0.0,
+    };
+
+    fn push(&mut self, value: i32) {
+        let delta = value as f32 - self.mean;
+
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.mean += delta / self.count as f32;
+        self.squares += delta * (value as f32 - self.mean);
+    }
+
+    fn merge(&mut self, other: &Accumulator) {
+        if other.count == 0 {
+            return;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.mean += delta * other.count as f32 / count as f32;
+        self.squares += other.squares + delta * delta * self.count as f32 * other.count as f32 / count as f32;
+        self.count = count;
+    }
+}
+
+// Statistics of several channels over window of `BUCKETS` time buckets. Bucket is discarded as whole when window moves,
+// so window covers from `BUCKETS - 1` to `BUCKETS` bucket durations. Buckets are aligned to multiples of bucket
+// duration, time moving back (clock set) clears window. Window moves on push and buckets older than window are also
+// skipped by summary, so values are not reported after gap in measurements.
+pub struct RollingStatistics<const CHANNELS: usize, const BUCKETS: usize> {
+    buckets: [[Accumulator; CHANNELS]; BUCKETS],
+    bucket_duration: u32,
+    bucket_start: Option<u32>,
+    current: usize,
+}
+
+impl<const CHANNELS: usize, const BUCKETS: usize> RollingStatistics<CHANNELS, BUCKETS> {
+    // Bucket duration in seconds.
+    pub const fn new(bucket_duration: u32) -> RollingStatistics<CHANNELS, BUCKETS> {
+        RollingStatistics {
+            buckets: [[Accumulator::EMPTY; CHANNELS]; BUCKETS],
+            bucket_duration,
+            bucket_start: None,
+            current: 0,
+        }
+    }
+
+    // Window duration in seconds.
+    pub fn duration(&self) -> u32 {
+        self.bucket_duration * BUCKETS as u32
+    }
+
+    // Changes bucket duration in seconds and clears window.
+    pub fn set_bucket_duration(&mut self, bucket_duration: u32) {
+        self.bucket_duration = bucket_duration;
+        self.clear();
+    }
+
+    pub fn push(&mut self, time: u32, values: [i32; CHANNELS]) {
+        let start = time - time % self.bucket_duration;
+
+        match self.bucket_start {
+            Some(bucket_start) if start >= bucket_start => {
+                let elapsed = ((start - bucket_start) / self.bucket_duration) as usize;
+
+                for _ in 0..elapsed.min(BUCKETS) {
+                    self.current = (self.current + 1) % BUCKETS;
+                    self.buckets[self.current] = [Accumulator::EMPTY; CHANNELS];
+                }
+            }
+            _ => self.clear(),
+        }
+
+        self.bucket_start = Some(start);
+
+        for (accumulator, value) in self.buckets[self.current].iter_mut().zip(values) {
+            accumulator.push(value);
+        }
+    }
+
+    // Summary of channel at time in seconds, `None` if window is empty.
+    pub fn summary(&self, time: u32, channel: usize) -> Option<Summary> {
+        let start = time - time % self.bucket_duration;
+        let elapsed = (start.saturating_sub(self.bucket_start?) / self.bucket_duration) as usize;
+        let mut total = Accumulator::EMPTY;
+
+        // Buckets from current one back to the oldest one still in window.
+        for age in 0..BUCKETS.saturating_sub(elapsed) {
+            total.merge(&self.buckets[(self.current + BUCKETS - age) % BUCKETS][channel]);
+        }
+
+        if total.count == 0 {
+            return None;
+        }
+
+        Some(Summary {
+            count: total.count,
+            min: total.min,
+            max: total.max,
+            mean: round(total.mean),
+            deviation: round(sqrt(total.squares / total.count as f32)),
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets = [[Accumulator::EMPTY; CHANNELS]; BUCKETS];
+        self.bucket_start = None;
+        self.current = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let mut statistics = RollingStatistics::<2, 4>::new(60);
+
+        for (time, value) in [(0, 2), (10, 4), (20, 4), (70, 4), (80, 5), (130, 5), (140, 7), (150, 9)] {
+            statistics.push(time, [value * 100, 100_000 + value * 10]);
+        }
+
+        let summary = Summary {
+            count: 8,
+            min: 200,
+            max: 900,
+            mean: 500,
+            deviation: 200,
+        };
+
+        assert_eq!(statistics.summary(150, 0), Some(summary));
+        assert_eq!(statistics.summary(150, 1).map(|summary| summary.mean), Some(100_050));
+        assert_eq!(statistics.summary(150, 1).map(|summary| summary.deviation), Some(20));
+    }
+
+    #[test]
+    fn rolling() {
+        let mut statistics = RollingStatistics::<1, 3>::new(60);
+
+        assert_eq!(statistics.duration(), 180);
+        assert_eq!(statistics.summary(0, 0), None);
+
+        statistics.push(0, [10]);
+        statistics.push(60, [20]);
+        statistics.push(120, [30]);
+        assert_eq!(statistics.summary(150, 0).map(|summary| summary.min), Some(10));
+
+        statistics.push(180, [40]);
+        assert_eq!(statistics.summary(180, 0).map(|summary| (summary.count, summary.min)), Some((3, 20)));
+
+        // Buckets age out without new values.
+        assert_eq!(statistics.summary(299, 0).map(|summary| (summary.count, summary.min)), Some((2, 30)));
+        assert_eq!(statistics.summary(300, 0).map(|summary| (summary.count, summary.min)), Some((1, 40)));
+        assert_eq!(statistics.summary(360, 0), None);
+
+        // Gap longer than window.
+        statistics.push(1_000, [50]);
+        assert_eq!(statistics.summary(1_000, 0).map(|summary| (summary.count, summary.min)), Some((1, 50)));
+
+        // Clock moved back.
+        statistics.push(100, [60]);
+        assert_eq!(statistics.summary(100, 0).map(|summary| (summary.count, summary.max)), Some((1, 60)));
+
+        statistics.set_bucket_duration(600);
+        assert_eq!(statistics.duration(), 1_800);
+        assert_eq!(statistics.summary(100, 0), None);
+
+        statistics.push(100, [70]);
+        statistics.clear();
+        assert_eq!(statistics.summary(100, 0), None);
+    }
+}
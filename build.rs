@@ -0,0 +1,23 @@
+use std::process::Command;
+
+// Embeds short hash of built commit as `GIT_HASH`, with `-dirty` suffix if working tree has uncommitted changes and
+// `unknown` outside of git repository.
+fn main() {
+    let hash = git(&["rev-parse", "--short=8", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|status| !status.is_empty());
+
+    println!("cargo:rustc-env=GIT_HASH={}{}", hash, if dirty { "-dirty" } else { "" });
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok().map(|output| output.trim().to_string())
+}
@@ -0,0 +1,212 @@
+use crate::parse::Reading;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+const NAME: &str = "bme280";
+const CSV_HEADER: &str =
+    "received,sequence,sensor,time,temperature_c,pressure_hpa,humidity_percent,tendency,pressure_change_hpa,forecast,flags";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    Csv,
+    Json, // JSON object per line.
+}
+
+impl LogFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            LogFormat::Csv => "csv",
+            LogFormat::Json => "jsonl",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    received: &'a str,
+    #[serde(flatten)]
+    reading: &'a Reading,
+}
+
+// Appends readings to `bme280.csv` or `bme280.jsonl` in log directory. When file would exceed maximum size, it is
+// renamed to `bme280.1.csv`, older files are shifted to next numbers and file above `keep` is removed.
+pub struct RotatingLog {
+    directory: PathBuf,
+    format: LogFormat,
+    max_size: u64,
+    keep: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLog {
+    pub fn open(directory: &Path, format: LogFormat, max_size: u64, keep: usize) -> IoResult<RotatingLog> {
+        fs::create_dir_all(directory)?;
+
+        let path = directory.join(file_name(format, 0));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(RotatingLog {
+            directory: directory.to_path_buf(),
+            format,
+            max_size,
+            keep,
+            file,
+            size,
+        })
+    }
+
+    // Received is host time, readings replayed from file or measured while collector was not running have
+    // device time only.
+    pub fn write(&mut self, received: &str, reading: &Reading) -> IoResult<()> {
+        let line = match self.format {
+            LogFormat::Csv => csv_row(received, reading),
+            LogFormat::Json => serde_json::to_string(&Entry { received, reading })? + "\n",
+        };
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        if self.size == 0 && self.format == LogFormat::Csv {
+            self.append(&format!("{}\n", CSV_HEADER))?;
+        }
+
+        self.append(&line)
+    }
+
+    fn append(&mut self, text: &str) -> IoResult<()> {
+        self.file.write_all(text.as_bytes())?;
+        self.size += text.len() as u64;
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> IoResult<()> {
+        let path = |number| self.directory.join(file_name(self.format, number));
+
+        if self.keep == 0 {
+            fs::remove_file(path(0))?;
+        } else {
+            // Missing files are skipped, the oldest one is overwritten by rename.
+            for number in (0..self.keep).rev() {
+                if path(number).exists() {
+                    fs::rename(path(number), path(number + 1))?;
+                }
+            }
+        }
+
+        self.file = OpenOptions::new().create(true).append(true).open(path(0))?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+fn file_name(format: LogFormat, number: usize) -> String {
+    match number {
+        0 => format!("{}.{}", NAME, format.extension()),
+        number => format!("{}.{}.{}", NAME, number, format.extension()),
+    }
+}
+
+fn csv_row(received: &str, reading: &Reading) -> String {
+    fn optional<T: ToString>(value: &Option<T>) -> String {
+        value.as_ref().map_or(String::new(), T::to_string)
+    }
+
+    format!(
+        "{},{},{},{},{},{},{},{},{},{},{}\n",
+        received,
+        optional(&reading.sequence),
+        optional(&reading.sensor),
+        optional(&reading.time),
+        reading.temperature,
+        reading.pressure,
+        reading.humidity,
+        optional(&reading.tendency),
+        optional(&reading.pressure_change),
+        optional(&reading.forecast),
+        optional(&reading.flags),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::reading;
+    use std::env;
+    use std::process;
+
+    fn numbered(sequence: u32) -> Reading {
+        Reading {
+            sequence: Some(sequence),
+            ..reading()
+        }
+    }
+
+    fn directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("collector-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        directory
+    }
+
+    #[test]
+    fn csv() {
+        let directory = directory("csv");
+        let mut log = RotatingLog::open(&directory, LogFormat::Csv, 1_000, 1).unwrap();
+        log.write("2026-10-19T12:34:56", &numbered(1)).unwrap();
+        drop(log);
+
+        // Header is not repeated after restart.
+        let mut log = RotatingLog::open(&directory, LogFormat::Csv, 1_000, 1).unwrap();
+        log.write("2026-10-19T12:35:56", &numbered(2)).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(directory.join("bme280.csv")).unwrap(),
+            format!(
+                "{}\n\
+                 2026-10-19T12:34:56,1,2035474b5356500e00240039,2026-10-19T12:34:56,21.34,1000,40,7,-1.8,U,17\n\
+                 2026-10-19T12:35:56,2,2035474b5356500e00240039,2026-10-19T12:34:56,21.34,1000,40,7,-1.8,U,17\n",
+                CSV_HEADER,
+            )
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn rotation() {
+        let directory = directory("rotation");
+        let mut log = RotatingLog::open(&directory, LogFormat::Json, 300, 2).unwrap();
+
+        for sequence in 1..=7 {
+            log.write("2026-10-19T12:34:56", &numbered(sequence)).unwrap();
+        }
+
+        let sequences = |number| {
+            fs::read_to_string(directory.join(file_name(LogFormat::Json, number)))
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["sequence"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // Each line is about 200 bytes, so every file keeps one reading.
+        assert_eq!(sequences(0), [7]);
+        assert_eq!(sequences(1), [6]);
+        assert_eq!(sequences(2), [5]);
+        assert!(!directory.join(file_name(LogFormat::Json, 3)).exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
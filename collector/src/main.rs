@@ -0,0 +1,208 @@
+mod log;
+mod metrics;
+mod mqtt;
+mod parse;
+
+use crate::log::LogFormat;
+use crate::log::RotatingLog;
+use crate::metrics::Metrics;
+use crate::mqtt::Publisher;
+use crate::mqtt::Topics;
+use crate::parse::Event;
+use crate::parse::Parser;
+use crate::parse::Reading;
+use clap::Parser as ArgumentParser;
+use std::error::Error;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+use telemetry::DateTime;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, ArgumentParser)]
+#[command(version, about = "Reads measurements of BME280 sensor from serial port and writes them to log files")]
+struct Arguments {
+    #[arg(
+        long,
+        required_unless_present = "replay",
+        conflicts_with = "replay",
+        help = "Serial port, for example /dev/ttyUSB0 or /dev/rfcomm0"
+    )]
+    port: Option<String>,
+
+    #[arg(long, default_value_t = 9600, help = "Baud rate of serial port")]
+    baud: u32,
+
+    #[arg(long, help = "Read output saved by --capture from file instead of serial port")]
+    replay: Option<PathBuf>,
+
+    #[arg(long, help = "Append all bytes received from serial port to file for later replay")]
+    capture: Option<PathBuf>,
+
+    #[arg(long, default_value = "logs", help = "Directory of log files")]
+    log_dir: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = LogFormat::Csv, help = "Format of log files")]
+    log_format: LogFormat,
+
+    #[arg(long, default_value_t = 10_000_000, help = "Maximum size of log file in bytes before rotation")]
+    max_size: u64,
+
+    #[arg(long, default_value_t = 5, help = "Number of rotated log files to keep")]
+    keep: usize,
+
+    #[arg(long, value_name = "ADDRESS", help = "Serve Prometheus metrics at /metrics, for example 0.0.0.0:9280")]
+    metrics: Option<String>,
+
+    #[arg(long, value_name = "BROKER", help = "Publish readings to MQTT broker, host with optional port")]
+    mqtt: Option<String>,
+
+    #[arg(long, default_value = "bme280", help = "Prefix of MQTT topics")]
+    mqtt_prefix: String,
+
+    #[arg(long, default_value = "homeassistant", help = "Prefix of Home Assistant discovery topics")]
+    discovery_prefix: String,
+
+    #[arg(long, requires = "mqtt", help = "User name for MQTT broker")]
+    mqtt_username: Option<String>,
+
+    #[arg(long, requires = "mqtt_username", help = "Password for MQTT broker")]
+    mqtt_password: Option<String>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let arguments = Arguments::parse();
+    let mut log = RotatingLog::open(&arguments.log_dir, arguments.log_format, arguments.max_size, arguments.keep)?;
+    let metrics = Arc::new(Mutex::new(Metrics::new()));
+    let server = match &arguments.metrics {
+        Some(address) => {
+            let (address, thread) = metrics::serve(address, metrics.clone())?;
+            eprintln!("Serving metrics at http://{}/metrics", address);
+
+            Some(thread)
+        }
+        None => None,
+    };
+    let mut publisher = match &arguments.mqtt {
+        Some(broker) => {
+            let credentials = arguments
+                .mqtt_username
+                .clone()
+                .map(|username| (username, arguments.mqtt_password.clone().unwrap_or_default()));
+            let topics = Topics::new(&arguments.mqtt_prefix, &arguments.discovery_prefix);
+
+            Some(Publisher::connect(broker, credentials, topics)?)
+        }
+        None => None,
+    };
+
+    match (&arguments.port, &arguments.replay) {
+        (_, Some(replay)) => {
+            collect(File::open(replay)?, None, &mut log, &metrics, publisher.as_mut())?;
+
+            if let Some(publisher) = publisher {
+                publisher.close();
+            }
+
+            // Replayed metrics stay available for scraping until collector is stopped.
+            if let Some(server) = server {
+                let _ = server.join();
+            }
+        }
+        (Some(port), None) => {
+            let port = serialport::new(port, arguments.baud).timeout(READ_TIMEOUT).open()?;
+            let capture = match &arguments.capture {
+                Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+                None => None,
+            };
+
+            collect(port, capture, &mut log, &metrics, publisher.as_mut())?;
+        }
+        (None, None) => unreachable!("port or replay is required"),
+    }
+
+    Ok(())
+}
+
+// Parses stream until end of file, serial port never ends. Readings are logged and written to standard output
+// together with other lines, so collector replaces terminal. All events update metrics, readings are published to
+// MQTT.
+fn collect<R>(
+    mut reader: R,
+    mut capture: Option<File>,
+    log: &mut RotatingLog,
+    metrics: &Mutex<Metrics>,
+    mut publisher: Option<&mut Publisher>,
+) -> Result<(), Box<dyn Error>>
+where
+    R: Read,
+{
+    let mut parser = Parser::new();
+    let mut buffer = [0; 256];
+
+    loop {
+        let length = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(length) => length,
+            Err(error) if error.kind() == ErrorKind::TimedOut || error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        if let Some(capture) = capture.as_mut() {
+            capture.write_all(&buffer[..length])?;
+        }
+
+        for byte in &buffer[..length] {
+            let event = match parser.push(*byte) {
+                Some(event) => event,
+                None => continue,
+            };
+
+            metrics
+                .lock()
+                .expect("metrics are not poisoned")
+                .push(&event, parser.sensor(), timestamp());
+
+            match event {
+                Event::Reading(reading) => {
+                    log.write(&DateTime::from_timestamp(timestamp() as u32).to_string(), &reading)?;
+
+                    if let Some(publisher) = publisher.as_mut() {
+                        publisher.publish(&reading);
+                    }
+
+                    println!("{}", summary(&reading));
+                }
+                Event::Text(line) => println!("{}", line),
+                Event::FrameError => eprintln!("Damaged binary frame."),
+            }
+        }
+    }
+}
+
+// Host time in seconds from Unix epoch.
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn summary(reading: &Reading) -> String {
+    format!(
+        "{} {}: {:.2} C, {:.2} hPa, {:.3}%, flags: {}",
+        reading.sensor.as_deref().unwrap_or("-"),
+        reading.time.as_deref().unwrap_or("-"),
+        reading.temperature,
+        reading.pressure,
+        reading.humidity,
+        reading.flags.map_or("-".to_string(), |flags| flags.to_string()),
+    )
+}
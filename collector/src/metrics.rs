@@ -0,0 +1,298 @@
+use crate::parse::Event;
+use crate::parse::Reading;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+use telemetry::Flags;
+use tiny_http::Header;
+use tiny_http::Response;
+use tiny_http::Server;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+const UNKNOWN_SENSOR: &str = "unknown";
+
+#[derive(Debug, Default)]
+struct Device {
+    reading: Option<Reading>,
+    last_seen: u64, // Host time of last reading, seconds from Unix epoch.
+    readings: u64,
+    sensor_errors: u64,
+    flagged: BTreeMap<&'static str, u64>, // Readings with each quality flag.
+}
+
+// Last reading and counters of each device, labelled by sensor ID. Readings without ID are labelled `unknown`, sensor
+// errors reported by firmware are counted for device which sent the last reading or boot message.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    devices: BTreeMap<String, Device>,
+    frame_errors: u64,
+}
+
+impl Metrics {
+    pub fn new() -> Metrics {
+        Metrics::default()
+    }
+
+    pub fn push(&mut self, event: &Event, sensor: Option<&str>, time: u64) {
+        match event {
+            Event::Reading(reading) => {
+                let device = self.device(reading.sensor.as_deref());
+                let flags = Flags(reading.flags.unwrap_or(0));
+
+                device.readings += 1;
+                device.last_seen = time;
+                device.reading = Some(reading.clone());
+
+                // Set clock is not quality problem.
+                for name in flags.names().filter(|name| *name != "clock_set") {
+                    *device.flagged.entry(name).or_default() += 1;
+                }
+            }
+            Event::Text(line) if line.starts_with("Sensor error") => self.device(sensor).sensor_errors += 1,
+            Event::Text(_) => {}
+            Event::FrameError => self.frame_errors += 1,
+        }
+    }
+
+    // Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+
+        self.family(
+            &mut text,
+            "bme280_temperature_celsius",
+            "gauge",
+            "Temperature.",
+            |device| device.reading.as_ref().map(|reading| reading.temperature),
+        );
+        self.family(&mut text, "bme280_pressure_pascals", "gauge", "Pressure.", |device| {
+            device
+                .reading
+                .as_ref()
+                .map(|reading| (reading.pressure * 100.0).round())
+        });
+        self.family(
+            &mut text,
+            "bme280_humidity_percent",
+            "gauge",
+            "Relative humidity.",
+            |device| device.reading.as_ref().map(|reading| reading.humidity),
+        );
+        self.family(&mut text, "bme280_dew_point_celsius", "gauge", "Dew point.", |device| {
+            device.reading.as_ref().and_then(Reading::dew_point)
+        });
+        self.family(
+            &mut text,
+            "bme280_last_seen_timestamp_seconds",
+            "gauge",
+            "Time of last reading.",
+            |device| Some(device.last_seen as f64),
+        );
+        self.family(
+            &mut text,
+            "bme280_readings_total",
+            "counter",
+            "Received readings.",
+            |device| Some(device.readings as f64),
+        );
+        self.family(
+            &mut text,
+            "bme280_sensor_errors_total",
+            "counter",
+            "Sensor errors reported by firmware.",
+            |device| Some(device.sensor_errors as f64),
+        );
+
+        header(
+            &mut text,
+            "bme280_flagged_readings_total",
+            "counter",
+            "Readings with quality flag.",
+        );
+
+        for (sensor, device) in &self.devices {
+            for (flag, count) in &device.flagged {
+                let _ = writeln!(
+                    text,
+                    "bme280_flagged_readings_total{{sensor=\"{}\",flag=\"{}\"}} {}",
+                    escape(sensor),
+                    flag,
+                    count,
+                );
+            }
+        }
+
+        header(
+            &mut text,
+            "bme280_frame_errors_total",
+            "counter",
+            "Damaged binary frames.",
+        );
+        let _ = writeln!(text, "bme280_frame_errors_total {}", self.frame_errors);
+
+        text
+    }
+
+    fn device(&mut self, sensor: Option<&str>) -> &mut Device {
+        self.devices
+            .entry(sensor.unwrap_or(UNKNOWN_SENSOR).to_string())
+            .or_default()
+    }
+
+    fn family<F>(&self, text: &mut String, name: &str, kind: &str, help: &str, value: F)
+    where
+        F: Fn(&Device) -> Option<f64>,
+    {
+        header(text, name, kind, help);
+
+        for (sensor, device) in &self.devices {
+            if let Some(value) = value(device) {
+                let _ = writeln!(text, "{}{{sensor=\"{}\"}} {}", name, escape(sensor), value);
+            }
+        }
+    }
+}
+
+// Serves `/metrics` in background thread, address with port 0 binds to any free port.
+pub fn serve(address: &str, metrics: Arc<Mutex<Metrics>>) -> Result<(SocketAddr, JoinHandle<()>), Box<dyn Error>> {
+    let server = Server::http(address).map_err(|error| format!("metrics server: {}", error))?;
+    let address = server
+        .server_addr()
+        .to_ip()
+        .ok_or("metrics address is not IP address")?;
+    let content_type = Header::from_bytes("Content-Type", CONTENT_TYPE).expect("valid header");
+
+    let thread = thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = match request.url().split('?').next() {
+                Some("/metrics") => {
+                    let text = metrics.lock().expect("metrics are not poisoned").render();
+
+                    Response::from_string(text).with_header(content_type.clone())
+                }
+                _ => Response::from_string("Not found.\n").with_status_code(404),
+            };
+
+            // Client may close connection before response.
+            let _ = request.respond(response);
+        }
+    });
+
+    Ok((address, thread))
+}
+
+fn header(text: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::reading;
+    use std::io::Read;
+    use std::io::Write as _;
+    use std::net::TcpStream;
+
+    const SENSOR: &str = "2035474b5356500e00240039";
+
+    fn event(sensor: Option<&str>, flags: u8) -> Event {
+        Event::Reading(Reading {
+            sensor: sensor.map(String::from),
+            flags: Some(flags),
+            ..reading()
+        })
+    }
+
+    fn metrics() -> Metrics {
+        let mut metrics = Metrics::new();
+        metrics.push(
+            &event(Some(SENSOR), Flags::CLOCK_SET | Flags::STALE),
+            None,
+            1_792_413_296,
+        );
+        metrics.push(&event(Some(SENSOR), Flags::STALE), None, 1_792_413_301);
+        metrics.push(
+            &Event::Text("Sensor error, reading rejected (rejected: 1).".to_string()),
+            Some(SENSOR),
+            0,
+        );
+        metrics.push(&Event::Text("OK.".to_string()), Some(SENSOR), 0);
+        metrics.push(&Event::FrameError, None, 0);
+        metrics.push(&event(None, 0), None, 1_792_413_302);
+
+        metrics
+    }
+
+    #[test]
+    fn render() {
+        let text = metrics().render();
+        let samples: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+
+        assert_eq!(
+            samples,
+            [
+                "bme280_temperature_celsius{sensor=\"2035474b5356500e00240039\"} 21.34",
+                "bme280_temperature_celsius{sensor=\"unknown\"} 21.34",
+                "bme280_pressure_pascals{sensor=\"2035474b5356500e00240039\"} 100000",
+                "bme280_pressure_pascals{sensor=\"unknown\"} 100000",
+                "bme280_humidity_percent{sensor=\"2035474b5356500e00240039\"} 40",
+                "bme280_humidity_percent{sensor=\"unknown\"} 40",
+                "bme280_dew_point_celsius{sensor=\"2035474b5356500e00240039\"} 7.18",
+                "bme280_dew_point_celsius{sensor=\"unknown\"} 7.18",
+                "bme280_last_seen_timestamp_seconds{sensor=\"2035474b5356500e00240039\"} 1792413301",
+                "bme280_last_seen_timestamp_seconds{sensor=\"unknown\"} 1792413302",
+                "bme280_readings_total{sensor=\"2035474b5356500e00240039\"} 2",
+                "bme280_readings_total{sensor=\"unknown\"} 1",
+                "bme280_sensor_errors_total{sensor=\"2035474b5356500e00240039\"} 1",
+                "bme280_sensor_errors_total{sensor=\"unknown\"} 0",
+                "bme280_flagged_readings_total{sensor=\"2035474b5356500e00240039\",flag=\"stale\"} 2",
+                "bme280_frame_errors_total 1",
+            ]
+        );
+        assert!(text
+            .starts_with("# HELP bme280_temperature_celsius Temperature.\n# TYPE bme280_temperature_celsius gauge\n"));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn http() {
+        let metrics = Arc::new(Mutex::new(metrics()));
+        let (address, _) = serve("127.0.0.1:0", metrics).unwrap();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(
+                stream,
+                "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+                path
+            )
+            .unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+
+            response
+        };
+
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(&format!("Content-Type: {}\r\n", CONTENT_TYPE)));
+        assert!(response.ends_with("bme280_frame_errors_total 1\n"));
+
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -0,0 +1,350 @@
+use crate::parse::Reading;
+use rumqttc::Client;
+use rumqttc::ConnectionError;
+use rumqttc::Event;
+use rumqttc::Incoming;
+use rumqttc::LastWill;
+use rumqttc::MqttOptions;
+use rumqttc::QoS;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::process;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 1883;
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const QUEUE_SIZE: usize = 1_000; // Messages waiting for connection, newer ones are dropped.
+const ONLINE: &str = "online";
+const OFFLINE: &str = "offline";
+
+// Channels announced to Home Assistant: key in state payload, name, device class and unit.
+const CHANNELS: [(&str, &str, &str, &str); 4] = [
+    ("temperature", "Temperature", "temperature", "°C"),
+    ("pressure", "Pressure", "atmospheric_pressure", "hPa"),
+    ("humidity", "Humidity", "humidity", "%"),
+    ("dew_point", "Dew point", "temperature", "°C"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub topic: String,
+    pub payload: String,
+    pub retain: bool, // Retained messages are published with QoS 1, others with QoS 0.
+}
+
+#[derive(Serialize)]
+struct State<'a> {
+    #[serde(flatten)]
+    reading: &'a Reading,
+    dew_point: Option<f64>,
+}
+
+// Topics and payloads independent of connection. Readings are published as JSON to `<prefix>/<sensor>/state`,
+// Home Assistant discovery configs of device are published before its first reading and again after `announce`,
+// because broker or Home Assistant may have lost them after restart.
+pub struct Topics {
+    prefix: String,
+    discovery_prefix: String,
+    announced: BTreeSet<String>,
+}
+
+impl Topics {
+    pub fn new(prefix: &str, discovery_prefix: &str) -> Topics {
+        Topics {
+            prefix: prefix.to_string(),
+            discovery_prefix: discovery_prefix.to_string(),
+            announced: BTreeSet::new(),
+        }
+    }
+
+    // `online` or `offline` state of collector, also availability of all entities.
+    pub fn status(&self) -> String {
+        format!("{}/status", self.prefix)
+    }
+
+    // Home Assistant publishes `online` here after start.
+    pub fn birth(&self) -> String {
+        format!("{}/status", self.discovery_prefix)
+    }
+
+    pub fn announce(&mut self) {
+        self.announced.clear();
+    }
+
+    // Readings without sensor ID are not published, Home Assistant entities are keyed by it.
+    pub fn messages(&mut self, reading: &Reading) -> Vec<Message> {
+        let sensor = match &reading.sensor {
+            Some(sensor) => topic_level(sensor),
+            None => return Vec::new(),
+        };
+        let state = format!("{}/{}/state", self.prefix, sensor);
+        let mut messages = Vec::new();
+
+        if !self.announced.contains(&sensor) {
+            messages.extend(CHANNELS.iter().map(|channel| self.discovery(&sensor, &state, channel)));
+            self.announced.insert(sensor);
+        }
+
+        let payload = State {
+            reading,
+            dew_point: reading.dew_point(),
+        };
+
+        messages.push(Message {
+            topic: state,
+            payload: serde_json::to_string(&payload).expect("reading is serializable"),
+            retain: true,
+        });
+
+        messages
+    }
+
+    fn discovery(&self, sensor: &str, state: &str, channel: &(&str, &str, &str, &str)) -> Message {
+        let (key, name, class, unit) = channel;
+        let config = json!({
+            "name": name,
+            "unique_id": format!("bme280_{}_{}", sensor, key),
+            "state_topic": state,
+            "value_template": format!("{{{{ value_json.{} }}}}", key),
+            "device_class": class,
+            "unit_of_measurement": unit,
+            "state_class": "measurement",
+            "availability_topic": self.status(),
+            "device": {
+                "identifiers": [format!("bme280_{}", sensor)],
+                "name": format!("BME280 {}", sensor),
+                "model": "BME280",
+            },
+        });
+
+        Message {
+            topic: format!("{}/sensor/bme280_{}/{}/config", self.discovery_prefix, sensor, key),
+            payload: config.to_string(),
+            retain: true,
+        }
+    }
+}
+
+// Publishes readings without blocking collector, connection is kept by background thread which reconnects after
+// errors. Broker publishes `offline` status when collector disconnects unexpectedly.
+pub struct Publisher {
+    client: Client,
+    topics: Topics,
+    announce: Arc<AtomicBool>, // Set after connection and Home Assistant restart.
+    stopping: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+    dropped: u64,
+}
+
+impl Publisher {
+    // Broker is host with optional port.
+    pub fn connect(
+        broker: &str,
+        credentials: Option<(String, String)>,
+        topics: Topics,
+    ) -> Result<Publisher, Box<dyn Error>> {
+        let (host, port) = match broker.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse()?),
+            None => (broker, DEFAULT_PORT),
+        };
+        let mut options = MqttOptions::new(format!("bme280-collector-{}", process::id()), host, port);
+
+        options.set_keep_alive(KEEP_ALIVE);
+        options.set_last_will(LastWill::new(topics.status(), OFFLINE, QoS::AtLeastOnce, true));
+
+        if let Some((username, password)) = credentials {
+            options.set_credentials(username, password);
+        }
+
+        let (client, mut connection) = Client::new(options, QUEUE_SIZE);
+        let announce = Arc::new(AtomicBool::new(false));
+        let stopping = Arc::new(AtomicBool::new(false));
+        let birth = topics.birth();
+
+        let thread = thread::spawn({
+            let announce = announce.clone();
+            let stopping = stopping.clone();
+
+            move || {
+                for event in connection.iter() {
+                    match event {
+                        Ok(Event::Incoming(Incoming::ConnAck(_))) => announce.store(true, Ordering::Relaxed),
+                        Ok(Event::Incoming(Incoming::Publish(publish))) => {
+                            if publish.topic == birth && publish.payload == ONLINE.as_bytes() {
+                                announce.store(true, Ordering::Relaxed);
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(ConnectionError::RequestsDone) => break,
+                        Err(_) if stopping.load(Ordering::Relaxed) => break,
+                        Err(error) => {
+                            eprintln!("MQTT error: {}.", error);
+                            thread::sleep(RECONNECT_DELAY);
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Publisher {
+            client,
+            topics,
+            announce,
+            stopping,
+            thread,
+            dropped: 0,
+        })
+    }
+
+    pub fn publish(&mut self, reading: &Reading) {
+        if self.announce.swap(false, Ordering::Relaxed) {
+            self.topics.announce();
+            self.send(Message {
+                topic: self.topics.status(),
+                payload: ONLINE.to_string(),
+                retain: true,
+            });
+
+            // Subscriptions are lost with connection.
+            let _ = self.client.try_subscribe(self.topics.birth(), QoS::AtLeastOnce);
+        }
+
+        for message in self.topics.messages(reading) {
+            self.send(message);
+        }
+    }
+
+    // Sends queued messages and `offline` status, which broker does not send after clean disconnection. Queue is
+    // dropped when broker is not connected.
+    pub fn close(self) {
+        self.stopping.store(true, Ordering::Relaxed);
+
+        let _ = self
+            .client
+            .try_publish(self.topics.status(), QoS::AtLeastOnce, true, OFFLINE);
+        let _ = self.client.disconnect();
+        drop(self.client);
+
+        let _ = self.thread.join();
+    }
+
+    fn send(&mut self, message: Message) {
+        let qos = if message.retain {
+            QoS::AtLeastOnce
+        } else {
+            QoS::AtMostOnce
+        };
+
+        if self
+            .client
+            .try_publish(message.topic, qos, message.retain, message.payload)
+            .is_err()
+        {
+            self.dropped += 1;
+
+            if self.dropped % 100 == 1 {
+                eprintln!("MQTT queue is full, messages dropped: {}.", self.dropped);
+            }
+        }
+    }
+}
+
+// Sensor ID is written by firmware, but CSV or JSON lines may be edited.
+fn topic_level(sensor: &str) -> String {
+    sensor
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::reading;
+    use serde_json::Value;
+
+    const SENSOR: &str = "2035474b5356500e00240039";
+
+    fn sensor_reading(sensor: Option<&str>) -> Reading {
+        Reading {
+            sensor: sensor.map(String::from),
+            ..reading()
+        }
+    }
+
+    fn json(message: &Message) -> Value {
+        serde_json::from_str(&message.payload).unwrap()
+    }
+
+    #[test]
+    fn discovery() {
+        let mut topics = Topics::new("bme280", "homeassistant");
+        let messages = topics.messages(&sensor_reading(Some(SENSOR)));
+
+        assert_eq!(
+            messages
+                .iter()
+                .map(|message| message.topic.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "homeassistant/sensor/bme280_2035474b5356500e00240039/temperature/config",
+                "homeassistant/sensor/bme280_2035474b5356500e00240039/pressure/config",
+                "homeassistant/sensor/bme280_2035474b5356500e00240039/humidity/config",
+                "homeassistant/sensor/bme280_2035474b5356500e00240039/dew_point/config",
+                "bme280/2035474b5356500e00240039/state",
+            ]
+        );
+        assert!(messages.iter().all(|message| message.retain));
+
+        let config = json(&messages[1]);
+        assert_eq!(config["unique_id"], "bme280_2035474b5356500e00240039_pressure");
+        assert_eq!(config["state_topic"], "bme280/2035474b5356500e00240039/state");
+        assert_eq!(config["value_template"], "{{ value_json.pressure }}");
+        assert_eq!(config["device_class"], "atmospheric_pressure");
+        assert_eq!(config["unit_of_measurement"], "hPa");
+        assert_eq!(config["availability_topic"], "bme280/status");
+        assert_eq!(config["device"]["identifiers"][0], "bme280_2035474b5356500e00240039");
+
+        // Configs are sent again only after announcement.
+        assert_eq!(topics.messages(&sensor_reading(Some(SENSOR))).len(), 1);
+        topics.announce();
+        assert_eq!(topics.messages(&sensor_reading(Some(SENSOR))).len(), 5);
+    }
+
+    #[test]
+    fn state() {
+        let mut topics = Topics::new("bme280", "homeassistant");
+        let messages = topics.messages(&sensor_reading(Some(SENSOR)));
+        let state = json(messages.last().unwrap());
+
+        assert_eq!(state["sequence"], 7);
+        assert_eq!(state["time"], "2026-10-19T12:34:56");
+        assert_eq!(state["temperature"], 21.34);
+        assert_eq!(state["pressure"], 1_000.0);
+        assert_eq!(state["humidity"], 40.0);
+        assert_eq!(state["dew_point"], 7.18);
+        assert_eq!(state["flags"], 17);
+
+        assert!(topics.messages(&sensor_reading(None)).is_empty());
+    }
+
+    #[test]
+    fn topic_levels() {
+        assert_eq!(topic_level(SENSOR), SENSOR);
+        assert_eq!(topic_level("a/b+#c d"), "a_b__c_d");
+    }
+}
@@ -0,0 +1,674 @@
+use serde::Serialize;
+use serde_json::Value;
+use telemetry::DateTime;
+use telemetry::Decoder;
+use telemetry::Flags;
+use telemetry::Frame;
+
+const MAX_LINE: usize = 1_024;
+const START_TIMESTAMP: u32 = 946_684_800; // 2000-01-01, firmware writes earlier time as seconds from start.
+const PASCALS_PER_MILLIMETRE_MERCURY: f64 = 133.322_387_415;
+
+// Measurement in units independent of firmware settings. Values missing in output format are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reading {
+    pub sequence: Option<u32>,
+    pub sensor: Option<String>,
+    pub time: Option<String>, // Time written by firmware, seconds from reset if clock is not set.
+    pub temperature: f64,     // C
+    pub pressure: f64,        // hPa
+    pub humidity: f64,        // %
+    pub tendency: Option<u8>,
+    pub pressure_change: Option<f64>, // hPa during last 3 hours.
+    pub forecast: Option<char>,
+    pub flags: Option<u8>,
+}
+
+impl Reading {
+    // Reading with firmware time, text format writes time set by `TIME` command as date.
+    fn new(time: &str) -> Reading {
+        let clock_set = !time.is_empty() && !time.ends_with(" s");
+
+        Reading {
+            sequence: None,
+            sensor: None,
+            time: Some(time.to_string()).filter(|time| !time.is_empty()),
+            temperature: 0.0,
+            pressure: 0.0,
+            humidity: 0.0,
+            tendency: None,
+            pressure_change: None,
+            forecast: None,
+            flags: if clock_set { Some(Flags::CLOCK_SET) } else { None },
+        }
+    }
+
+    // Dew point in C, calculated the same way as by firmware.
+    pub fn dew_point(&self) -> Option<f64> {
+        let temperature = (self.temperature * 100.0).round() as i32;
+        let humidity = (self.humidity * 1_024.0).round() as u32;
+
+        bme280::dew_point(temperature, humidity).map(|dew_point| dew_point as f64 / 100.0)
+    }
+}
+
+// Reading of examples in tests of all modules, tests change fields they check.
+#[cfg(test)]
+pub fn reading() -> Reading {
+    Reading {
+        sequence: Some(7),
+        sensor: Some("2035474b5356500e00240039".to_string()),
+        time: Some("2026-10-19T12:34:56".to_string()),
+        temperature: 21.34,
+        pressure: 1_000.0,
+        humidity: 40.0,
+        tendency: Some(7),
+        pressure_change: Some(-1.8),
+        forecast: Some('U'),
+        flags: Some(Flags::CLOCK_SET | Flags::STALE),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Reading(Reading),
+    Text(String), // Line which is not a measurement, for example reply to command.
+    FrameError,   // Damaged binary frame.
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PressureUnit {
+    Pascal,
+    Hectopascal,
+    MillimetreMercury,
+}
+
+// Splits byte stream to lines and binary frames and parses measurements in any output format of firmware. CSV rows
+// are parsed only after header, which is written after reset, `FORMAT` and `UNITS` commands. Text and NMEA formats
+// have no sensor ID, their readings get ID from boot message or `INFO` reply.
+pub struct Parser {
+    decoder: Decoder,
+    line: Vec<u8>,
+    columns: Option<Vec<String>>,
+    sensor: Option<String>,
+}
+
+impl Parser {
+    pub fn new() -> Parser {
+        Parser {
+            decoder: Decoder::new(),
+            line: Vec::new(),
+            columns: None,
+            sensor: None,
+        }
+    }
+
+    // ID of sensor which sent the last reading or boot message.
+    pub fn sensor(&self) -> Option<&str> {
+        self.sensor.as_deref()
+    }
+
+    pub fn push(&mut self, byte: u8) -> Option<Event> {
+        match self.decoder.push(byte) {
+            Some(Ok(frame)) => {
+                // Frame bytes were collected as line too.
+                self.line.clear();
+
+                return Some(Event::Reading(self.identify(frame_reading(&frame))));
+            }
+            Some(Err(_)) => return Some(Event::FrameError),
+            None => {}
+        }
+
+        // Frame bytes may contain line ends.
+        if self.decoder.in_frame() {
+            self.line.clear();
+
+            return None;
+        }
+
+        if byte == b'\r' || byte == b'\n' {
+            let line = String::from_utf8_lossy(&self.line).into_owned();
+            self.line.clear();
+
+            if line.is_empty() {
+                return None;
+            }
+
+            return Some(match self.parse_line(&line) {
+                Some(reading) => Event::Reading(self.identify(reading)),
+                None => Event::Text(line),
+            });
+        }
+
+        if self.line.len() < MAX_LINE {
+            self.line.push(byte);
+        }
+
+        None
+    }
+
+    pub fn parse_line(&mut self, line: &str) -> Option<Reading> {
+        if line.starts_with("sequence,sensor,time,") {
+            self.columns = Some(line.split(',').map(String::from).collect());
+
+            return None;
+        }
+
+        // `Device: 2035474b5356500e00240039, firmware: ...`
+        if let Some(identity) = line.strip_prefix("Device: ") {
+            self.sensor = identity.split(',').next().map(String::from);
+
+            return None;
+        }
+
+        if line.starts_with('{') {
+            json_reading(line)
+        } else if let Some(line) = line.strip_prefix("bme280,") {
+            influx_reading(line)
+        } else if line.starts_with("$WIXDR,") {
+            nmea_reading(line)
+        } else if line.contains(", T: ") {
+            text_reading(line)
+        } else {
+            csv_reading(self.columns.as_deref()?, line)
+        }
+    }
+}
+
+impl Parser {
+    fn identify(&mut self, mut reading: Reading) -> Reading {
+        match &reading.sensor {
+            Some(sensor) => self.sensor = Some(sensor.clone()),
+            None => reading.sensor = self.sensor.clone(),
+        }
+
+        reading
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
+
+// `2026-10-19T12:34:56, T: 21.34 C, P: 750.0 mmHg, H: 40.000%, tendency: -1.80 hPa/3h (7), forecast: U - ...`
+fn text_reading(line: &str) -> Option<Reading> {
+    let mut parts = line.split(", ");
+    let time = parts.next()?;
+    let mut reading = Reading::new(time);
+    let mut values = 0;
+
+    for part in parts {
+        if let Some(value) = part.strip_prefix("T: ") {
+            let (value, unit) = value.split_once(' ')?;
+            reading.temperature = temperature(value.parse().ok()?, temperature_unit(unit)?);
+            values += 1;
+        } else if let Some(value) = part.strip_prefix("P: ") {
+            let (value, unit) = value.split_once(' ')?;
+            reading.pressure = pressure(value.parse().ok()?, pressure_unit(unit)?);
+            values += 1;
+        } else if let Some(value) = part.strip_prefix("H: ") {
+            reading.humidity = value.strip_suffix('%')?.parse().ok()?;
+            values += 1;
+        } else if let Some(value) = part.strip_prefix("tendency: ") {
+            let (change, code) = value.split_once(" hPa/3h ")?;
+            reading.pressure_change = Some(change.parse().ok()?);
+            reading.tendency = Some(code.trim_matches(|c| c == '(' || c == ')').parse().ok()?);
+        } else if let Some(value) = part.strip_prefix("forecast: ") {
+            reading.forecast = value.chars().next();
+        } else if let Some(names) = part.strip_prefix("flags: ") {
+            for name in names.split(' ') {
+                reading.flags = Some(reading.flags.unwrap_or(0) | Flags::from_name(name)?);
+            }
+        }
+    }
+
+    if values == 3 {
+        Some(reading)
+    } else {
+        None
+    }
+}
+
+fn csv_reading(columns: &[String], line: &str) -> Option<Reading> {
+    let values: Vec<&str> = line.split(',').collect();
+
+    if values.len() != columns.len() {
+        return None;
+    }
+
+    let mut reading = Reading::new("");
+    let mut measured = 0;
+
+    for (column, value) in columns.iter().zip(values) {
+        match column.as_str() {
+            "sequence" => reading.sequence = Some(value.parse().ok()?),
+            "sensor" => reading.sensor = Some(value.to_string()),
+            "time" => reading.time = Some(value.to_string()),
+            "humidity_percent" => {
+                reading.humidity = value.parse().ok()?;
+                measured += 1;
+            }
+            "tendency" => reading.tendency = optional(value)?,
+            "pressure_change_hpa" => reading.pressure_change = optional(value)?,
+            "forecast" => reading.forecast = value.chars().next(),
+            "flags" => reading.flags = optional(value)?,
+            column => {
+                if let Some(unit) = column.strip_prefix("temperature_") {
+                    reading.temperature = temperature(value.parse().ok()?, temperature_unit(unit)?);
+                    measured += 1;
+                } else if let Some(unit) = column.strip_prefix("pressure_") {
+                    reading.pressure = pressure(value.parse().ok()?, pressure_unit(unit)?);
+                    measured += 1;
+                }
+            }
+        }
+    }
+
+    if measured == 3 {
+        Some(reading)
+    } else {
+        None
+    }
+}
+
+// Alarm objects have no temperature and are not parsed.
+fn json_reading(line: &str) -> Option<Reading> {
+    let object: Value = serde_json::from_str(line).ok()?;
+    let number = |key: &str| object.get(key).and_then(Value::as_f64);
+    let text = |key: &str| object.get(key).and_then(Value::as_str);
+    let mut reading = Reading::new(text("time")?);
+
+    reading.sequence = number("sequence").map(|sequence| sequence as u32);
+    reading.sensor = text("sensor").map(String::from);
+    reading.temperature = temperature(number("temperature")?, temperature_unit(text("temperature_unit")?)?);
+    reading.pressure = pressure(number("pressure")?, pressure_unit(text("pressure_unit")?)?);
+    reading.humidity = number("humidity")?;
+    reading.tendency = number("tendency").map(|tendency| tendency as u8);
+    reading.pressure_change = number("pressure_change");
+    reading.forecast = text("forecast").and_then(|forecast| forecast.chars().next());
+    reading.flags = number("flags").map(|flags| flags as u8);
+
+    Some(reading)
+}
+
+// `sensor=<id> sequence=7i,temperature_c=21.34,...,flags=49i 1792413296000000000`, measurement name is stripped.
+fn influx_reading(line: &str) -> Option<Reading> {
+    let mut parts = line.split(' ');
+    let sensor = parts.next()?.strip_prefix("sensor=")?;
+    let fields = parts.next()?;
+    let time = match parts.next() {
+        Some(nanoseconds) => {
+            let seconds = u32::try_from(nanoseconds.parse::<u64>().ok()? / 1_000_000_000).ok()?;
+
+            Some(DateTime::from_timestamp(seconds).to_string())
+        }
+        None => None,
+    };
+    let mut reading = Reading::new("");
+    let mut measured = 0;
+
+    reading.sensor = Some(sensor.to_string());
+    reading.time = time;
+
+    for field in fields.split(',') {
+        let (key, value) = field.split_once('=')?;
+        let integer = value.strip_suffix('i');
+
+        match key {
+            "sequence" => reading.sequence = Some(integer?.parse().ok()?),
+            "humidity_percent" => {
+                reading.humidity = value.parse().ok()?;
+                measured += 1;
+            }
+            "tendency" => reading.tendency = Some(integer?.parse().ok()?),
+            "pressure_change_hpa" => reading.pressure_change = Some(value.parse().ok()?),
+            "forecast" => reading.forecast = value.trim_matches('"').chars().next(),
+            "flags" => reading.flags = Some(integer?.parse().ok()?),
+            key => {
+                if let Some(unit) = key.strip_prefix("temperature_") {
+                    reading.temperature = temperature(value.parse().ok()?, temperature_unit(unit)?);
+                    measured += 1;
+                } else if let Some(unit) = key.strip_prefix("pressure_") {
+                    reading.pressure = pressure(value.parse().ok()?, pressure_unit(unit)?);
+                    measured += 1;
+                }
+            }
+        }
+    }
+
+    if measured == 3 {
+        Some(reading)
+    } else {
+        None
+    }
+}
+
+// `$WIXDR` sentence has transducers of 4 fields: type, value, unit and name. `$WIMDA` repeats the same values.
+fn nmea_reading(line: &str) -> Option<Reading> {
+    let (sentence, checksum) = line.strip_prefix('$')?.split_once('*')?;
+
+    if u8::from_str_radix(checksum, 16).ok()? != sentence.bytes().fold(0, |checksum, byte| checksum ^ byte) {
+        return None;
+    }
+
+    let fields: Vec<&str> = sentence.split(',').skip(1).collect();
+    let mut reading = Reading::new("");
+    let mut measured = 0;
+
+    for transducer in fields.chunks(4) {
+        match transducer {
+            [_, value, _, "AIR"] => {
+                reading.temperature = value.parse().ok()?;
+                measured += 1;
+            }
+            [_, value, _, "BARO"] => {
+                reading.pressure = round(value.parse::<f64>().ok()? * 1_000.0, 2);
+                measured += 1;
+            }
+            [_, value, _, "RH"] => {
+                reading.humidity = value.parse().ok()?;
+                measured += 1;
+            }
+            [_, value, _, "FLAGS"] => reading.flags = Some(value.parse().ok()?),
+            _ => {}
+        }
+    }
+
+    if measured == 3 {
+        Some(reading)
+    } else {
+        None
+    }
+}
+
+fn frame_reading(frame: &Frame) -> Reading {
+    let id = &frame.device_id;
+    let word = |index: usize| u32::from_le_bytes([id[index], id[index + 1], id[index + 2], id[index + 3]]);
+    let time = if frame.flags.contains(Flags::CLOCK_SET) {
+        DateTime::from_timestamp(frame.time).to_string()
+    } else {
+        format!("{} s", frame.time.saturating_sub(START_TIMESTAMP))
+    };
+
+    Reading {
+        sequence: Some(frame.sequence),
+        sensor: Some(format!("{:08x}{:08x}{:08x}", word(8), word(4), word(0))),
+        time: Some(time),
+        temperature: frame.temperature as f64 / 100.0,
+        pressure: round(frame.pressure as f64 / 25_600.0, 2),
+        humidity: round(frame.humidity as f64 / 1_024.0, 3),
+        tendency: None,
+        pressure_change: None,
+        forecast: None,
+        flags: Some(frame.flags.0),
+    }
+}
+
+// Empty CSV column is `None`, invalid value is error.
+fn optional<T>(value: &str) -> Option<Option<T>>
+where
+    T: std::str::FromStr,
+{
+    if value.is_empty() {
+        Some(None)
+    } else {
+        value.parse().ok().map(Some)
+    }
+}
+
+fn temperature_unit(unit: &str) -> Option<TemperatureUnit> {
+    match unit {
+        "C" | "c" => Some(TemperatureUnit::Celsius),
+        "F" | "f" => Some(TemperatureUnit::Fahrenheit),
+        _ => None,
+    }
+}
+
+fn pressure_unit(unit: &str) -> Option<PressureUnit> {
+    match unit.to_ascii_lowercase().as_str() {
+        "pa" => Some(PressureUnit::Pascal),
+        "hpa" => Some(PressureUnit::Hectopascal),
+        "mmhg" => Some(PressureUnit::MillimetreMercury),
+        _ => None,
+    }
+}
+
+fn temperature(value: f64, unit: TemperatureUnit) -> f64 {
+    match unit {
+        TemperatureUnit::Celsius => value,
+        TemperatureUnit::Fahrenheit => round((value - 32.0) * 5.0 / 9.0, 2),
+    }
+}
+
+fn pressure(value: f64, unit: PressureUnit) -> f64 {
+    match unit {
+        PressureUnit::Pascal => round(value / 100.0, 2),
+        PressureUnit::Hectopascal => value,
+        PressureUnit::MillimetreMercury => round(value * PASCALS_PER_MILLIMETRE_MERCURY / 100.0, 2),
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10_f64.powi(decimals);
+
+    (value * factor).round() / factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENSOR: &str = "2035474b5356500e00240039";
+
+    fn parse(lines: &[&str]) -> Vec<Event> {
+        let mut parser = Parser::new();
+
+        lines
+            .iter()
+            .flat_map(|line| line.bytes().chain(*b"\r\n"))
+            .filter_map(|byte| parser.push(byte))
+            .collect()
+    }
+
+    #[test]
+    fn text() {
+        let events = parse(&[
+            "2026-10-19T12:34:56, T: 21.34 C, P: 1000.000 hPa, H: 40.000%, tendency: -1.80 hPa/3h (7), \
+             forecast: U - Occasional rain, worsening, flags: stale",
+            "OK.",
+            "35 s, T: 70.00 F, P: 750.0 mmHg, H: 40.000%",
+        ]);
+
+        let expected = Reading {
+            sequence: None,
+            sensor: None,
+            ..reading()
+        };
+        let uptime = Reading {
+            time: Some("35 s".to_string()),
+            temperature: 21.11,
+            pressure: 999.92,
+            tendency: None,
+            pressure_change: None,
+            forecast: None,
+            flags: None,
+            ..expected.clone()
+        };
+
+        assert_eq!(
+            events,
+            [
+                Event::Reading(expected),
+                Event::Text("OK.".to_string()),
+                Event::Reading(uptime),
+            ]
+        );
+    }
+
+    #[test]
+    fn identity() {
+        let banner = format!("Device: {}, firmware: 0.1.0 (1a2b3c4d), sensor: BME280 (chip ID 0x60) at address 0x76", SENSOR);
+        let mut parser = Parser::new();
+        let events: Vec<Event> = banner
+            .bytes()
+            .chain(*b"\r\n35 s, T: 21.34 C, P: 1000.000 hPa, H: 40.000%\r\n")
+            .filter_map(|byte| parser.push(byte))
+            .collect();
+
+        assert_eq!(parser.sensor(), Some(SENSOR));
+        assert_eq!(events[0], Event::Text(banner));
+        assert!(matches!(&events[1], Event::Reading(reading) if reading.sensor.as_deref() == Some(SENSOR)));
+    }
+
+    #[test]
+    fn csv() {
+        let header =
+            "sequence,sensor,time,temperature_c,pressure_pa,humidity_percent,tendency,pressure_change_hpa,forecast,flags";
+        let row = format!("7,{},2026-10-19T12:34:56,21.34,100000.00,40.000,7,-1.80,U,17", SENSOR);
+        let events = parse(&[&row, header, &row]);
+
+        assert_eq!(
+            events,
+            [
+                Event::Text(row.clone()),
+                Event::Text(header.to_string()),
+                Event::Reading(reading()),
+            ]
+        );
+
+        let header = header.replace("temperature_c,pressure_pa", "temperature_f,pressure_hpa");
+        let events = parse(&[&header, &format!("8,{},12 s,32.00,1000.000,40.000,,,,0", SENSOR)]);
+
+        let expected = Reading {
+            sequence: Some(8),
+            time: Some("12 s".to_string()),
+            temperature: 0.0,
+            tendency: None,
+            pressure_change: None,
+            forecast: None,
+            flags: Some(0),
+            ..reading()
+        };
+
+        assert_eq!(events, [Event::Text(header), Event::Reading(expected)]);
+    }
+
+    #[test]
+    fn json() {
+        let line = format!(
+            "{{\"sequence\":7,\"sensor\":\"{}\",\"time\":\"2026-10-19T12:34:56\",\"temperature\":21.34,\
+             \"temperature_unit\":\"C\",\"pressure\":750.1,\"pressure_unit\":\"mmHg\",\"humidity\":40.000,\
+             \"humidity_unit\":\"%\",\"tendency\":7,\"pressure_change\":-1.80,\"pressure_change_unit\":\"hPa/3h\",\
+             \"forecast\":\"U\",\"forecast_text\":\"Occasional rain, worsening\",\"flags\":17}}",
+            SENSOR,
+        );
+        let alarm = "{\"alarm\":\"storm\",\"active\":true,\"value\":3.10,\"unit\":\"hPa/3h\"}";
+
+        let expected = Reading {
+            pressure: 1_000.05,
+            ..reading()
+        };
+
+        assert_eq!(
+            parse(&[&line, alarm]),
+            [Event::Reading(expected), Event::Text(alarm.to_string())]
+        );
+    }
+
+    #[test]
+    fn influx() {
+        let line = format!(
+            "bme280,sensor={} sequence=7i,temperature_c=21.34,pressure_hpa=1000.000,humidity_percent=40.000,\
+             tendency=7i,pressure_change_hpa=-1.80,forecast=\"U\",flags=17i 1792413296000000000",
+            SENSOR,
+        );
+        let alarm = format!("bme280_alarm,sensor={},alarm=storm active=true,value=3.10", SENSOR);
+
+        assert_eq!(
+            parse(&[&line, &alarm]),
+            [Event::Reading(reading()), Event::Text(alarm.clone())]
+        );
+    }
+
+    #[test]
+    fn nmea() {
+        let events = parse(&[
+            "$WIMDA,29.53,I,1.0000,B,21.34,C,,C,40.000,,7.18,C,,T,,M,,N,,M*18",
+            "$WIXDR,C,21.34,C,AIR,P,1.0000,B,BARO,H,40.000,P,RH,C,7.18,C,DEWPOINT,G,49,,FLAGS*34",
+            "$WIXDR,C,21.34,C,AIR,P,1.0000,B,BARO,H,40.000,P,RH,C,7.18,C,DEWPOINT,G,49,,FLAGS*35",
+        ]);
+
+        let expected = Reading {
+            sequence: None,
+            sensor: None,
+            time: None,
+            tendency: None,
+            pressure_change: None,
+            forecast: None,
+            flags: Some(49),
+            ..reading()
+        };
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1], Event::Reading(expected));
+        assert!(matches!(events[2], Event::Text(_)));
+    }
+
+    #[test]
+    fn binary() {
+        let frame = Frame {
+            device_id: [
+                0x39, 0x00, 0x24, 0x00, 0x0E, 0x50, 0x56, 0x53, 0x4B, 0x47, 0x35, 0x20,
+            ],
+            sequence: 7,
+            time: 1_792_413_296,
+            temperature: 2_134,
+            pressure: 25_600_000,
+            humidity: 40_960,
+            flags: Flags(Flags::CLOCK_SET | Flags::STALE),
+        };
+        let mut stream = b"OK.\r\n".to_vec();
+        stream.extend_from_slice(&frame.encode());
+        stream.extend_from_slice(
+            &Frame {
+                time: START_TIMESTAMP + 35,
+                flags: Flags(0),
+                ..frame
+            }
+            .encode(),
+        );
+
+        let mut parser = Parser::new();
+        let events: Vec<Event> = stream.into_iter().filter_map(|byte| parser.push(byte)).collect();
+
+        let expected = Reading {
+            tendency: None,
+            pressure_change: None,
+            forecast: None,
+            ..reading()
+        };
+        let uptime = Reading {
+            time: Some("35 s".to_string()),
+            flags: Some(0),
+            ..expected.clone()
+        };
+
+        assert_eq!(
+            events,
+            [
+                Event::Text("OK.".to_string()),
+                Event::Reading(expected),
+                Event::Reading(uptime),
+            ]
+        );
+    }
+}
@@ -16,6 +16,7 @@ PROVIDE(SPI1 = DefaultHandler);
 PROVIDE(SPI2 = DefaultHandler);
 PROVIDE(TIM14 = DefaultHandler);
 PROVIDE(TIM16 = DefaultHandler);
+PROVIDE(TIM17 = DefaultHandler);
 PROVIDE(TIM1_BRK_UP_TRG_COM = DefaultHandler);
 PROVIDE(TIM1_CC = DefaultHandler);
 PROVIDE(TIM2 = DefaultHandler);
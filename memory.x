@@ -1,5 +1,15 @@
 MEMORY
 {
-  FLASH : ORIGIN = 0x08000000, LENGTH = 32K
-  RAM : ORIGIN = 0x20000000, LENGTH = 8K
+  /* Last two flash pages (4K) reserved for settings storage. */
+  FLASH : ORIGIN = 0x08000000, LENGTH = 32K - 4K
+  RAM : ORIGIN = 0x20000000, LENGTH = 8K - 128
+  PANIC : ORIGIN = 0x20000000 + 8K - 128, LENGTH = 128
 }
+
+SECTIONS
+{
+  .panic (NOLOAD) : ALIGN(4)
+  {
+    KEEP(*(.panic .panic.*));
+  } > PANIC
+} INSERT AFTER .bss;
@@ -0,0 +1,190 @@
+use crate::history::History;
+use telemetry::alarm::AlarmKind;
+use telemetry::alarm::ALARM_KINDS;
+
+const DEBOUNCE: u8 = 3; // Consecutive measurements which raise or clear alarm.
+const PRESSURE_DROP_WINDOW: u32 = 10_800; // Seconds, pressure drop is compared per 3 hours.
+const MIN_PRESSURE_DROP_WINDOW: u32 = 3_600; // Seconds, shorter history is not used for storm warning.
+const PATTERN_STEPS: u8 = 16;
+
+// Alarm thresholds, `None` if alarm is disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmThresholds([Option<i32>; 5]);
+
+impl AlarmThresholds {
+    pub const fn disabled() -> AlarmThresholds {
+        AlarmThresholds([None; 5])
+    }
+
+    pub fn get(&self, kind: AlarmKind) -> Option<i32> {
+        self.0[kind.index()]
+    }
+
+    // Returns `false` if threshold is out of range.
+    pub fn set(&mut self, kind: AlarmKind, threshold: Option<i32>) -> bool {
+        let (min, max) = kind.range();
+
+        match threshold {
+            Some(value) if value < min || value > max => false,
+            _ => {
+                self.0[kind.index()] = threshold;
+                true
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmEvent {
+    pub kind: AlarmKind,
+    pub active: bool,
+    pub value: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AlarmState {
+    active: bool,
+    count: u8, // Consecutive measurements crossing threshold.
+}
+
+pub struct Alarms {
+    states: [AlarmState; 5],
+    pattern_kind: usize,
+    pattern_step: u8,
+}
+
+impl Alarms {
+    pub const fn new() -> Alarms {
+        Alarms {
+            states: [AlarmState {
+                active: false,
+                count: 0,
+            }; 5],
+            pattern_kind: 0,
+            pattern_step: 0,
+        }
+    }
+
+    // Alarm is raised when value is beyond threshold for `DEBOUNCE` measurements and cleared when value is back
+    // by hysteresis for `DEBOUNCE` measurements. Missing value (pressure drop without enough history) keeps state.
+    pub fn update(&mut self, thresholds: &AlarmThresholds, kind: AlarmKind, value: Option<i32>) -> Option<AlarmEvent> {
+        let state = &mut self.states[kind.index()];
+
+        let threshold = match thresholds.get(kind) {
+            Some(threshold) => threshold,
+            None => {
+                state.active = false;
+                state.count = 0;
+
+                return None;
+            }
+        };
+        let value = value?;
+
+        let crossing = match (is_high(kind), state.active) {
+            (true, false) => value > threshold,
+            (true, true) => value < threshold - hysteresis(kind),
+            (false, false) => value < threshold,
+            (false, true) => value > threshold + hysteresis(kind),
+        };
+
+        if !crossing {
+            state.count = 0;
+
+            return None;
+        }
+
+        state.count += 1;
+
+        if state.count < DEBOUNCE {
+            return None;
+        }
+
+        state.active = !state.active;
+        state.count = 0;
+
+        Some(AlarmEvent {
+            kind,
+            active: state.active,
+            value,
+        })
+    }
+
+    pub fn is_active(&self, kind: AlarmKind) -> bool {
+        self.states[kind.index()].active
+    }
+
+    // Bit per active alarm in order of `ALARM_KINDS`.
+    pub fn active_mask(&self) -> u16 {
+        ALARM_KINDS
+            .iter()
+            .filter(|kind| self.is_active(**kind))
+            .fold(0, |mask, kind| mask | 1 << kind.index())
+    }
+
+    // Advances LED pattern by one step, patterns of active alarms are shown in turn. Returns LED state.
+    pub fn pattern_step(&mut self) -> bool {
+        if self.active_mask() == 0 {
+            self.pattern_step = 0;
+
+            return false;
+        }
+
+        if self.pattern_step == 0 || !self.states[self.pattern_kind].active {
+            self.pattern_kind = (1..=ALARM_KINDS.len())
+                .map(|offset| (self.pattern_kind + offset) % ALARM_KINDS.len())
+                .find(|index| self.states[*index].active)
+                .unwrap_or(0);
+        }
+
+        let pattern = pattern(ALARM_KINDS[self.pattern_kind]);
+        let on = pattern & (1 << (PATTERN_STEPS - 1 - self.pattern_step)) != 0;
+        self.pattern_step = (self.pattern_step + 1) % PATTERN_STEPS;
+
+        on
+    }
+}
+
+// Pressure drop in Pa per 3 hours from oldest sample measured between 1 and 3 hours ago, positive if pressure falls.
+pub fn pressure_drop(history: &History, time: u32, pressure: u32) -> Option<i32> {
+    let pressure = (pressure >> 8) as i32;
+
+    history
+        .iter()
+        .map(|(_, sample)| sample)
+        .find(|sample| {
+            let age = time.wrapping_sub(sample.time());
+
+            (MIN_PRESSURE_DROP_WINDOW..=PRESSURE_DROP_WINDOW).contains(&age)
+        })
+        .map(|sample| {
+            let age = time.wrapping_sub(sample.time()) as i32;
+            let drop = (sample.measurement().pressure >> 8) as i32 - pressure;
+
+            drop * PRESSURE_DROP_WINDOW as i32 / age
+        })
+}
+
+// Distance from threshold which clears alarm: 0.5 C, 2% and 0.5 hPa per 3 hours.
+fn hysteresis(kind: AlarmKind) -> i32 {
+    match kind {
+        AlarmKind::TemperatureHigh | AlarmKind::TemperatureLow => 50,
+        AlarmKind::HumidityHigh | AlarmKind::HumidityLow => 200,
+        AlarmKind::PressureDrop => 50,
+    }
+}
+
+fn is_high(kind: AlarmKind) -> bool {
+    !matches!(kind, AlarmKind::TemperatureLow | AlarmKind::HumidityLow)
+}
+
+// LED pattern of 16 steps by 125 ms, LED is on for set bits starting from the highest one.
+fn pattern(kind: AlarmKind) -> u16 {
+    match kind {
+        AlarmKind::TemperatureHigh => 0b1000_0000_0000_0000,
+        AlarmKind::TemperatureLow => 0b1010_0000_0000_0000,
+        AlarmKind::HumidityHigh => 0b1110_0000_0000_0000,
+        AlarmKind::HumidityLow => 0b1110_1000_0000_0000,
+        AlarmKind::PressureDrop => 0b1111_1111_0000_0000,
+    }
+}
@@ -0,0 +1,167 @@
+use core::fmt::Arguments;
+use core::fmt::Write;
+use stm32g0xx_hal::hal::serial::Read;
+use stm32g0xx_hal::hal::timer::CountDown;
+use stm32g0xx_hal::rcc::Rcc;
+use stm32g0xx_hal::stm32::USART2;
+use stm32g0xx_hal::time::MicroSecond;
+use stm32g0xx_hal::time::U32Ext;
+use telemetry::bluetooth::BluetoothModule;
+use telemetry::bluetooth::Name;
+use telemetry::bluetooth::Pin;
+use telemetry::command::BAUD_RATES;
+
+const RESPONSE_TIMEOUT_US: u32 = 1_000_000;
+const HC05_COMMAND_BAUD_RATE: u32 = 38_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvisionError {
+    Write,
+    NoResponse,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BluetoothSettings {
+    pub module: BluetoothModule,
+    pub name: Name,
+    pub pin: Pin,
+    pub baud_rate: u32, // Module baud rate after provisioning.
+    pub pending: bool,  // Provisioning is done on next start.
+}
+
+// Configures module by AT commands, module must not be connected, otherwise commands are sent to connected device.
+// HC-05 must be in command mode (KEY pin held high at power on), which works at 38400 baud regardless of data baud
+// rate, so USART is switched to 38400 baud for AT commands and back to `baud_rate` after them. HC-06 and HM-10 detect
+// end of command by pause, so each command waits for response for 1 s. New baud rate is used by module after response
+// (HC-06), after reset (HM-10) or after leaving command mode (HC-05).
+pub fn provision<TX, RX, T>(
+    tx: &mut TX,
+    rx: &mut RX,
+    timer: &mut T,
+    rcc: &Rcc,
+    baud_rate: u32,
+    settings: &BluetoothSettings,
+) -> Result<(), ProvisionError>
+where
+    TX: Write,
+    RX: Read<u8>,
+    T: CountDown<Time = MicroSecond>,
+{
+    match settings.module {
+        BluetoothModule::None => Ok(()),
+        BluetoothModule::Hc05 => {
+            set_baud_rate(rcc, HC05_COMMAND_BAUD_RATE);
+            let result = configure(tx, rx, timer, settings);
+            set_baud_rate(rcc, baud_rate);
+
+            result
+        }
+        _ => configure(tx, rx, timer, settings),
+    }
+}
+
+fn configure<TX, RX, T>(
+    tx: &mut TX,
+    rx: &mut RX,
+    timer: &mut T,
+    settings: &BluetoothSettings,
+) -> Result<(), ProvisionError>
+where
+    TX: Write,
+    RX: Read<u8>,
+    T: CountDown<Time = MicroSecond>,
+{
+    let module = settings.module;
+
+    exchange(tx, rx, timer, module, format_args!("AT"))?;
+
+    if !settings.name.is_empty() {
+        match module {
+            BluetoothModule::Hc05 => exchange(tx, rx, timer, module, format_args!("AT+NAME={}", settings.name))?,
+            _ => exchange(tx, rx, timer, module, format_args!("AT+NAME{}", settings.name))?,
+        }
+    }
+
+    if !settings.pin.is_empty() {
+        match module {
+            BluetoothModule::Hc05 => exchange(tx, rx, timer, module, format_args!("AT+PSWD={}", settings.pin))?,
+            BluetoothModule::Hm10 => {
+                exchange(tx, rx, timer, module, format_args!("AT+PASS{}", settings.pin))?;
+                exchange(tx, rx, timer, module, format_args!("AT+TYPE2"))?;
+            }
+            _ => exchange(tx, rx, timer, module, format_args!("AT+PIN{}", settings.pin))?,
+        }
+    }
+
+    let baud_index = BAUD_RATES
+        .iter()
+        .position(|baud_rate| *baud_rate == settings.baud_rate)
+        .unwrap_or(3);
+
+    match module {
+        BluetoothModule::Hc05 => exchange(tx, rx, timer, module, format_args!("AT+UART={},0,0", settings.baud_rate)),
+        BluetoothModule::Hm10 => {
+            // HM-10 numbering: 0 - 9600, 1 - 19200, 2 - 38400, 3 - 57600, 4 - 115200, 5 - 4800, 6 - 2400, 7 - 1200.
+            const HM10_BAUD: [u8; 8] = [7, 6, 5, 0, 1, 2, 3, 4];
+
+            exchange(tx, rx, timer, module, format_args!("AT+BAUD{}", HM10_BAUD[baud_index]))?;
+            exchange(tx, rx, timer, module, format_args!("AT+RESET"))
+        }
+        // HC-06 numbering starts from 1 - 1200.
+        _ => exchange(tx, rx, timer, module, format_args!("AT+BAUD{}", baud_index + 1)),
+    }
+}
+
+// Sends command and checks that response starts with `OK`.
+fn exchange<TX, RX, T>(
+    tx: &mut TX,
+    rx: &mut RX,
+    timer: &mut T,
+    module: BluetoothModule,
+    command: Arguments,
+) -> Result<(), ProvisionError>
+where
+    TX: Write,
+    RX: Read<u8>,
+    T: CountDown<Time = MicroSecond>,
+{
+    while rx.read().is_ok() {}
+
+    tx.write_fmt(command).map_err(|_| ProvisionError::Write)?;
+
+    if module == BluetoothModule::Hc05 {
+        tx.write_str("\r\n").map_err(|_| ProvisionError::Write)?;
+    }
+
+    let mut response = [0; 2];
+    let mut length = 0;
+
+    // USART has no FIFO, receiver is polled without pause, otherwise bytes are overrun above 9600 baud.
+    timer.start(RESPONSE_TIMEOUT_US.us());
+
+    while timer.wait().is_err() {
+        if let Ok(byte) = rx.read() {
+            if length < response.len() {
+                response[length] = byte;
+                length += 1;
+            }
+        }
+    }
+
+    if response == *b"OK" {
+        Ok(())
+    } else {
+        Err(ProvisionError::NoResponse)
+    }
+}
+
+// HAL sets baud rate only when serial port is created. Last byte is sent before USART is disabled for change.
+#[allow(unsafe_code)]
+fn set_baud_rate(rcc: &Rcc, baud_rate: u32) {
+    let usart = unsafe { &*USART2::ptr() };
+
+    while usart.isr.read().tc().bit_is_clear() {}
+    usart.cr1.modify(|_, w| w.ue().clear_bit());
+    usart.brr.write(|w| unsafe { w.bits(rcc.clocks.apb_clk.0 / baud_rate) });
+    usart.cr1.modify(|_, w| w.ue().set_bit());
+}
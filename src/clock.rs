@@ -0,0 +1,90 @@
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use stm32g0xx_hal::rcc::Rcc;
+use stm32g0xx_hal::rtc::Rtc;
+use stm32g0xx_hal::rtc::RtcExt;
+use stm32g0xx_hal::stm32::RTC;
+use stm32g0xx_hal::time::Date;
+use stm32g0xx_hal::time::Time;
+use telemetry::DateTime;
+
+// RTC starts from this date after power on, timestamps before clock is set written as seconds from start.
+const START_DATE: DateTime = DateTime {
+    year: 2000,
+    month: 1,
+    day: 1,
+    hours: 0,
+    minutes: 0,
+    seconds: 0,
+};
+
+// Writes timestamp as ISO-8601 date and time if clock was set, otherwise as seconds since clock start.
+pub struct Timestamp(pub u32);
+
+impl Timestamp {
+    pub fn is_set(&self) -> bool {
+        DateTime::from_timestamp(self.0).is_set()
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.is_set() {
+            write!(f, "{}", DateTime::from_timestamp(self.0))
+        } else {
+            write!(f, "{} s", self.0.saturating_sub(START_DATE.timestamp()))
+        }
+    }
+}
+
+// Real-time clock clocked from LSI, keeps running during STOP mode and MCU resets.
+pub struct Clock {
+    rtc: Rtc,
+}
+
+impl Clock {
+    pub fn new(rtc: RTC, rcc: &mut Rcc) -> Clock {
+        let mut clock = Clock {
+            rtc: rtc.constrain(rcc),
+        };
+
+        if clock.now().year < START_DATE.year {
+            clock.set(&START_DATE);
+        }
+
+        clock
+    }
+
+    pub fn now(&self) -> DateTime {
+        let date = self.rtc.get_date();
+        let time = self.rtc.get_time();
+
+        DateTime {
+            year: date.year as u16,
+            month: date.month as u8,
+            day: date.day as u8,
+            hours: time.hours as u8,
+            minutes: time.minutes as u8,
+            seconds: time.seconds as u8,
+        }
+    }
+
+    pub fn timestamp(&self) -> u32 {
+        self.now().timestamp()
+    }
+
+    pub fn set(&mut self, date_time: &DateTime) {
+        self.rtc.set_date(&Date {
+            year: date_time.year as u32,
+            month: date_time.month as u32,
+            day: date_time.day as u32,
+        });
+        self.rtc.set_time(&Time {
+            hours: date_time.hours as u32,
+            minutes: date_time.minutes as u32,
+            seconds: date_time.seconds as u32,
+            daylight_savings: false,
+        });
+    }
+}
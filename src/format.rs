@@ -0,0 +1,722 @@
+#[cfg(feature = "alarms")]
+use crate::alarm::AlarmEvent;
+#[cfg(feature = "alarms")]
+use crate::alarm::Alarms;
+use crate::clock::Timestamp;
+use crate::identity::DeviceId;
+use crate::identity::FIRMWARE_VERSION;
+use crate::identity::GIT_HASH;
+use crate::output::OutputWrite;
+use crate::sensor::Chip;
+use crate::sensor::Measurement;
+use crate::settings::Settings;
+#[cfg(feature = "statistics")]
+use crate::statistics::HUMIDITY;
+#[cfg(feature = "statistics")]
+use crate::statistics::PRESSURE;
+#[cfg(feature = "statistics")]
+use crate::statistics::TEMPERATURE;
+#[cfg(feature = "forecast")]
+use crate::weather::Weather;
+#[cfg(feature = "nmea")]
+use bme280::dew_point;
+use bme280::HumidityExt;
+use bme280::PressureExt;
+#[cfg(feature = "statistics")]
+use bme280::RollingStatistics;
+use core::fmt::Display;
+#[cfg(feature = "binary")]
+use core::fmt::Error as FmtError;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+#[cfg(feature = "binary")]
+use nb::block;
+#[cfg(feature = "alarms")]
+use telemetry::alarm::AlarmKind;
+#[cfg(feature = "alarms")]
+use telemetry::alarm::ALARM_KINDS;
+#[cfg(feature = "bluetooth")]
+use telemetry::bluetooth::BluetoothModule;
+use telemetry::command::OutputFormat;
+use telemetry::command::PowerMode;
+use telemetry::command::PressureUnit;
+use telemetry::command::TemperatureUnit;
+use telemetry::Flags;
+#[cfg(feature = "binary")]
+use telemetry::Frame;
+#[cfg(feature = "nmea")]
+use telemetry::Sentence;
+
+const CELSIUS: i32 = 100;
+#[cfg(feature = "nmea")]
+const PASCALS_PER_BAR: u64 = 100_000;
+#[cfg(feature = "nmea")]
+const PASCALS_PER_INCH_MERCURY: u64 = 3_386_389; // Pascals in 1000 inHg.
+#[cfg(feature = "nmea")]
+const PRESSURE_FACTOR: u64 = 256; // Pressure is in Q24.8 fixed point.
+
+// Temperature in 0.01 C written in selected unit with sign, `TemperatureExt` loses sign between -1 and 0 degrees.
+struct Temperature(i32, TemperatureUnit);
+
+impl Display for Temperature {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let value = match self.1 {
+            TemperatureUnit::Celsius => self.0,
+            TemperatureUnit::Fahrenheit => self.0 * 9 / 5 + 3_200,
+        };
+        let sign = if value < 0 { "-" } else { "" };
+
+        write!(f, "{}{}.{:02}", sign, value.abs() / CELSIUS, value.abs() % CELSIUS)
+    }
+}
+
+// Parts are written as `u32`, so formatting code of `u16` is not linked.
+struct Pressure(u32, PressureUnit);
+
+impl Display for Pressure {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.1 {
+            PressureUnit::Pascal => {
+                let pressure = self.0.pa();
+                write!(f, "{}.{:02}", pressure.0, pressure.1 as u32)
+            }
+            PressureUnit::Hectopascal => {
+                let pressure = self.0.hpa();
+                write!(f, "{}.{:03}", pressure.0 as u32, pressure.1 as u32)
+            }
+            PressureUnit::MillimetreMercury => {
+                let pressure = self.0.mmhg();
+                write!(f, "{}.{:01}", pressure.0 as u32, pressure.1 as u32)
+            }
+        }
+    }
+}
+
+// Pressure in bar with 4 decimal places, used in NMEA sentences.
+#[cfg(feature = "nmea")]
+struct Bar(u32);
+
+#[cfg(feature = "nmea")]
+impl Display for Bar {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let value = (self.0 as u64 * 10_000 + PRESSURE_FACTOR * PASCALS_PER_BAR / 2)
+            / (PRESSURE_FACTOR * PASCALS_PER_BAR);
+        write!(f, "{}.{:04}", value / 10_000, value % 10_000)
+    }
+}
+
+// Pressure in inches of mercury with 2 decimal places, used in NMEA sentences.
+#[cfg(feature = "nmea")]
+struct InchesMercury(u32);
+
+#[cfg(feature = "nmea")]
+impl Display for InchesMercury {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let value = (self.0 as u64 * 100_000 + PRESSURE_FACTOR * PASCALS_PER_INCH_MERCURY / 2)
+            / (PRESSURE_FACTOR * PASCALS_PER_INCH_MERCURY);
+        write!(f, "{}.{:02}", value / 100, value % 100)
+    }
+}
+
+// Dew point in C, empty if it can not be calculated.
+#[cfg(feature = "nmea")]
+struct DewPoint(Option<i32>);
+
+#[cfg(feature = "nmea")]
+impl Display for DewPoint {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.0 {
+            Some(dew_point) => write!(f, "{}", Temperature(dew_point, TemperatureUnit::Celsius)),
+            None => Ok(()),
+        }
+    }
+}
+
+// Signed value in hundredths written with 2 decimal places.
+#[cfg(any(feature = "alarms", feature = "forecast", feature = "statistics"))]
+struct Hundredths(i32);
+
+#[cfg(any(feature = "alarms", feature = "forecast", feature = "statistics"))]
+impl Display for Hundredths {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let sign = if self.0 < 0 { "-" } else { "" };
+
+        write!(f, "{}{}.{:02}", sign, self.0.abs() / 100, self.0.abs() % 100)
+    }
+}
+
+// Alarm threshold or measured value, temperature in C regardless of units, pressure drop in hPa per 3 hours.
+#[cfg(feature = "alarms")]
+struct AlarmValue(AlarmKind, i32);
+
+#[cfg(feature = "alarms")]
+impl Display for AlarmValue {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.0 {
+            AlarmKind::TemperatureHigh | AlarmKind::TemperatureLow => {
+                write!(f, "{}", Temperature(self.1, TemperatureUnit::Celsius))
+            }
+            AlarmKind::HumidityHigh | AlarmKind::HumidityLow | AlarmKind::PressureDrop => {
+                write!(f, "{}", Hundredths(self.1))
+            }
+        }
+    }
+}
+
+// Difference of temperatures in 0.01 C written in selected unit.
+#[cfg(feature = "statistics")]
+struct TemperatureDifference(i32, TemperatureUnit);
+
+#[cfg(feature = "statistics")]
+impl Display for TemperatureDifference {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.1 {
+            TemperatureUnit::Celsius => write!(f, "{}", Hundredths(self.0)),
+            TemperatureUnit::Fahrenheit => write!(f, "{}", Hundredths(self.0 * 9 / 5)),
+        }
+    }
+}
+
+struct Humidity(u32);
+
+impl Display for Humidity {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let humidity = self.0.percent();
+        write!(f, "{}.{:03}", humidity.0 as u32, humidity.1 as u32)
+    }
+}
+
+// Seconds since reset written as days and time.
+pub struct Uptime(pub u32);
+
+impl Display for Uptime {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let seconds = self.0;
+
+        write!(
+            f,
+            "{}d {:02}:{:02}:{:02}",
+            seconds / 86_400,
+            seconds / 3_600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+        )
+    }
+}
+
+// Names of flags separated by spaces.
+struct FlagNames(Flags);
+
+impl Display for FlagNames {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for (index, name) in self.0.names().enumerate() {
+            write!(f, "{}{}", if index == 0 { "" } else { " " }, name)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Writes CSV header, other formats have no header.
+pub fn write_header(writer: &mut dyn Write, settings: &Settings) -> FmtResult {
+    match settings.output_format {
+        OutputFormat::Csv => writeln!(
+            writer,
+            "sequence,sensor,time,temperature_{},pressure_{},humidity_percent,tendency,pressure_change_hpa,forecast,flags",
+            temperature_field(settings.temperature_unit),
+            pressure_field(settings.pressure_unit),
+        ),
+        OutputFormat::Text
+        | OutputFormat::Json
+        | OutputFormat::Influx
+        | OutputFormat::Binary
+        | OutputFormat::Nmea => Ok(()),
+    }
+}
+
+pub fn write_measurement(
+    writer: &mut dyn OutputWrite,
+    sequence: u32,
+    timestamp: u32,
+    measurement: &Measurement,
+    #[cfg(feature = "forecast")] weather: Option<&Weather>,
+    settings: &Settings,
+) -> FmtResult {
+    let time = Timestamp(timestamp);
+    let temperature = Temperature(measurement.temperature, settings.temperature_unit);
+    let pressure = Pressure(measurement.pressure, settings.pressure_unit);
+    let humidity = Humidity(measurement.humidity);
+    let mut flags = measurement.flags;
+
+    if time.is_set() {
+        flags.insert(Flags::CLOCK_SET);
+    }
+
+    match settings.output_format {
+        OutputFormat::Text => {
+            write!(
+                writer,
+                "{}, T: {} {}, P: {} {}, H: {}%",
+                time,
+                temperature,
+                temperature_symbol(settings.temperature_unit),
+                pressure,
+                pressure_symbol(settings.pressure_unit),
+                humidity,
+            )?;
+
+            #[cfg(feature = "forecast")]
+            if let Some(weather) = weather {
+                write!(
+                    writer,
+                    ", tendency: {} hPa/3h ({}), forecast: {} - {}",
+                    Hundredths(weather.change),
+                    weather.tendency.code(),
+                    weather.forecast.letter(),
+                    weather.forecast.description(),
+                )?;
+            }
+
+            // Time already shows whether clock is set, only quality flags are written.
+            if measurement.flags != Flags::default() {
+                write!(writer, ", flags: {}", FlagNames(measurement.flags))?;
+            }
+
+            writeln!(writer)
+        }
+        OutputFormat::Csv => {
+            write!(
+                writer,
+                "{},{},{},{},{},{},",
+                sequence,
+                DeviceId::read(),
+                time,
+                temperature,
+                pressure,
+                humidity,
+            )?;
+
+            // Columns of tendency are empty until history covers 3 hours.
+            #[cfg(feature = "forecast")]
+            match weather {
+                Some(weather) => write!(
+                    writer,
+                    "{},{},{},",
+                    weather.tendency.code(),
+                    Hundredths(weather.change),
+                    weather.forecast.letter(),
+                )?,
+                None => write!(writer, ",,,")?,
+            }
+            #[cfg(not(feature = "forecast"))]
+            write!(writer, ",,,")?;
+
+            writeln!(writer, "{}", flags.0)
+        }
+        OutputFormat::Json => {
+            write!(
+                writer,
+                "{{\"sequence\":{},\"sensor\":\"{}\",\"time\":\"{}\",\
+                 \"temperature\":{},\"temperature_unit\":\"{}\",\
+                 \"pressure\":{},\"pressure_unit\":\"{}\",\
+                 \"humidity\":{},\"humidity_unit\":\"%\"",
+                sequence,
+                DeviceId::read(),
+                time,
+                temperature,
+                temperature_symbol(settings.temperature_unit),
+                pressure,
+                pressure_symbol(settings.pressure_unit),
+                humidity,
+            )?;
+
+            #[cfg(feature = "forecast")]
+            if let Some(weather) = weather {
+                write!(
+                    writer,
+                    ",\"tendency\":{},\"pressure_change\":{},\"pressure_change_unit\":\"hPa/3h\",\
+                     \"forecast\":\"{}\",\"forecast_text\":\"{}\"",
+                    weather.tendency.code(),
+                    Hundredths(weather.change),
+                    weather.forecast.letter(),
+                    weather.forecast.description(),
+                )?;
+            }
+
+            writeln!(writer, ",\"flags\":{}}}", flags.0)
+        }
+        OutputFormat::Influx => {
+            write!(
+                writer,
+                "bme280,sensor={} sequence={}i,temperature_{}={},pressure_{}={},humidity_percent={}",
+                DeviceId::read(),
+                sequence,
+                temperature_field(settings.temperature_unit),
+                temperature,
+                pressure_field(settings.pressure_unit),
+                pressure,
+                humidity,
+            )?;
+
+            #[cfg(feature = "forecast")]
+            if let Some(weather) = weather {
+                write!(
+                    writer,
+                    ",tendency={}i,pressure_change_hpa={},forecast=\"{}\"",
+                    weather.tendency.code(),
+                    Hundredths(weather.change),
+                    weather.forecast.letter(),
+                )?;
+            }
+
+            write!(writer, ",flags={}i", flags.0)?;
+
+            // Without time set by `TIME` command database assigns time of arrival.
+            if time.is_set() {
+                write!(writer, " {}000000000", timestamp)?;
+            }
+
+            writeln!(writer)
+        }
+        #[cfg(feature = "binary")]
+        OutputFormat::Binary => {
+            let frame = Frame {
+                device_id: DeviceId::read().to_bytes(),
+                sequence,
+                time: timestamp,
+                temperature: measurement.temperature,
+                pressure: measurement.pressure,
+                humidity: measurement.humidity,
+                flags,
+            };
+
+            for byte in frame.encode() {
+                block!(writer.write(byte)).map_err(|_| FmtError)?;
+            }
+
+            Ok(())
+        }
+        #[cfg(feature = "nmea")]
+        OutputFormat::Nmea => {
+            // Marine instruments expect temperatures in C and pressure in bar and inHg regardless of units.
+            let temperature = Temperature(measurement.temperature, TemperatureUnit::Celsius);
+            let dew_point = DewPoint(dew_point(measurement.temperature, measurement.humidity));
+            let bar = Bar(measurement.pressure);
+
+            let mut sentence = Sentence::start(writer, "WIMDA")?;
+            write!(
+                sentence,
+                ",{},I,{},B,{},C,,C,{},,{},C,,T,,M,,N,,M",
+                InchesMercury(measurement.pressure),
+                bar,
+                temperature,
+                humidity,
+                dew_point,
+            )?;
+            sentence.finish()?;
+
+            let mut sentence = Sentence::start(writer, "WIXDR")?;
+            write!(
+                sentence,
+                ",C,{},C,AIR,P,{},B,BARO,H,{},P,RH,C,{},C,DEWPOINT,G,{},,FLAGS",
+                temperature, bar, humidity, dew_point, flags.0,
+            )?;
+            sentence.finish()
+        }
+        // Rejected by settings.
+        #[cfg(not(feature = "binary"))]
+        OutputFormat::Binary => Ok(()),
+        #[cfg(not(feature = "nmea"))]
+        OutputFormat::Nmea => Ok(()),
+    }
+}
+
+#[cfg(feature = "alarms")]
+pub fn write_alarm(writer: &mut dyn Write, event: &AlarmEvent, timestamp: u32, settings: &Settings) -> FmtResult {
+    let time = Timestamp(timestamp);
+    let value = AlarmValue(event.kind, event.value);
+    let unit = alarm_unit(event.kind);
+
+    match settings.output_format {
+        OutputFormat::Text => writeln!(
+            writer,
+            "{}: {}, {}{}.",
+            if event.active { "Alarm" } else { "Alarm cleared" },
+            alarm_name(event.kind),
+            value,
+            unit,
+        ),
+        OutputFormat::Json => writeln!(
+            writer,
+            "{{\"alarm\":\"{}\",\"active\":{},\"value\":{},\"unit\":\"{}\",\"sensor\":\"{}\",\"time\":\"{}\"}}",
+            alarm_field(event.kind),
+            event.active,
+            value,
+            unit.trim_start(),
+            DeviceId::read(),
+            time,
+        ),
+        OutputFormat::Influx => {
+            write!(
+                writer,
+                "bme280_alarm,sensor={},alarm={} active={},value={}",
+                DeviceId::read(),
+                alarm_field(event.kind),
+                event.active,
+                value,
+            )?;
+
+            if time.is_set() {
+                write!(writer, " {}000000000", timestamp)?;
+            }
+
+            writeln!(writer)
+        }
+        #[cfg(feature = "nmea")]
+        OutputFormat::Nmea => {
+            let mut sentence = Sentence::start(writer, "WIALR")?;
+            sentence.write_char(',')?;
+
+            if time.is_set() {
+                let seconds = timestamp % 86_400;
+                write!(sentence, "{:02}{:02}{:02}.00", seconds / 3_600, seconds / 60 % 60, seconds % 60)?;
+            }
+
+            write!(
+                sentence,
+                ",{:03},{},V,",
+                event.kind.index() + 1,
+                if event.active { "A" } else { "V" },
+            )?;
+
+            for c in alarm_name(event.kind).chars() {
+                sentence.write_char(c.to_ascii_uppercase())?;
+            }
+
+            sentence.finish()
+        }
+        // Alarm rows would break columns of CSV and frames do not carry alarms.
+        OutputFormat::Csv | OutputFormat::Binary => Ok(()),
+        #[cfg(not(feature = "nmea"))]
+        OutputFormat::Nmea => Ok(()),
+    }
+}
+
+#[cfg(feature = "alarms")]
+pub fn write_alarms(writer: &mut dyn Write, alarms: &Alarms, settings: &Settings) -> FmtResult {
+    write!(writer, "Alarms:")?;
+
+    for (index, kind) in ALARM_KINDS.iter().enumerate() {
+        let separator = if index == 0 { "" } else { "," };
+
+        match settings.alarms.get(*kind) {
+            Some(threshold) => write!(
+                writer,
+                "{} {} {}{}{}",
+                separator,
+                alarm_name(*kind),
+                AlarmValue(*kind, threshold),
+                alarm_unit(*kind),
+                if alarms.is_active(*kind) { " (active)" } else { "" },
+            )?,
+            None => write!(writer, "{} {} off", separator, alarm_name(*kind))?,
+        }
+    }
+
+    writeln!(writer)
+}
+
+#[cfg(feature = "statistics")]
+pub fn write_statistics<const BUCKETS: usize>(
+    writer: &mut dyn Write,
+    window: &RollingStatistics<3, BUCKETS>,
+    time: u32,
+    settings: &Settings,
+) -> FmtResult {
+    let hours = window.duration() / 3_600;
+
+    let (temperature, pressure, humidity) = match (
+        window.summary(time, TEMPERATURE),
+        window.summary(time, PRESSURE),
+        window.summary(time, HUMIDITY),
+    ) {
+        (Some(temperature), Some(pressure), Some(humidity)) => (temperature, pressure, humidity),
+        _ => return writeln!(writer, "Statistics for {} h: no measurements.", hours),
+    };
+    let temperature_unit = settings.temperature_unit;
+    let pressure_unit = settings.pressure_unit;
+
+    writeln!(writer, "Statistics for {} h ({} measurements):", hours, temperature.count)?;
+    writeln!(
+        writer,
+        "T: min {}, max {}, mean {}, deviation {} {}",
+        Temperature(temperature.min, temperature_unit),
+        Temperature(temperature.max, temperature_unit),
+        Temperature(temperature.mean, temperature_unit),
+        TemperatureDifference(temperature.deviation, temperature_unit),
+        temperature_symbol(temperature_unit),
+    )?;
+    writeln!(
+        writer,
+        "P: min {}, max {}, mean {}, deviation {} {}",
+        Pressure((pressure.min as u32) << 8, pressure_unit),
+        Pressure((pressure.max as u32) << 8, pressure_unit),
+        Pressure((pressure.mean as u32) << 8, pressure_unit),
+        Pressure((pressure.deviation as u32) << 8, pressure_unit),
+        pressure_symbol(pressure_unit),
+    )?;
+    writeln!(
+        writer,
+        "H: min {}, max {}, mean {}, deviation {}%",
+        Humidity(humidity.min as u32),
+        Humidity(humidity.max as u32),
+        Humidity(humidity.mean as u32),
+        Humidity(humidity.deviation as u32),
+    )
+}
+
+// Writes unique ID of MCU, firmware version with commit and chip of connected sensor.
+pub fn write_identity(writer: &mut dyn Write, chip: Option<Chip>) -> FmtResult {
+    write!(
+        writer,
+        "Device: {}, firmware: {} ({}), sensor: ",
+        DeviceId::read(),
+        FIRMWARE_VERSION,
+        GIT_HASH,
+    )?;
+
+    match chip {
+        Some(chip) => writeln!(
+            writer,
+            "{} (chip ID 0x{:02x}) at address 0x{:02x}",
+            chip_name(chip.id),
+            chip.id,
+            chip.address,
+        ),
+        None => writeln!(writer, "not found"),
+    }
+}
+
+pub fn write_settings(writer: &mut dyn Write, settings: &Settings) -> FmtResult {
+    let power_mode = match settings.power_mode {
+        PowerMode::Sleep => "sleep",
+        PowerMode::Stop => "stop",
+    };
+    let output_format = match settings.output_format {
+        OutputFormat::Text => "text",
+        OutputFormat::Csv => "csv",
+        OutputFormat::Json => "json",
+        OutputFormat::Influx => "influx",
+        OutputFormat::Binary => "binary",
+        OutputFormat::Nmea => "nmea",
+    };
+    write!(
+        writer,
+        "Interval: {} s, units: {} {}, oversample: T{} P{} H{}, filter: {}, power: {}, format: {}, baud: {}",
+        settings.interval,
+        temperature_symbol(settings.temperature_unit),
+        pressure_symbol(settings.pressure_unit),
+        settings.sampling.temperature.factor(),
+        settings.sampling.pressure.factor(),
+        settings.sampling.humidity.factor(),
+        settings.sampling.filter.coefficient(),
+        power_mode,
+        output_format,
+        settings.baud_rate,
+    )?;
+
+    #[cfg(feature = "bluetooth")]
+    {
+        let bluetooth_module = match settings.bluetooth.module {
+            BluetoothModule::None => "none",
+            BluetoothModule::Hc05 => "hc05",
+            BluetoothModule::Hc06 => "hc06",
+            BluetoothModule::Hm10 => "hm10",
+        };
+        let pending = if settings.bluetooth.pending { " (pending)" } else { "" };
+
+        write!(writer, ", bluetooth: {}{}", bluetooth_module, pending)?;
+    }
+
+    #[cfg(feature = "forecast")]
+    write!(writer, ", altitude: {} m", settings.altitude)?;
+
+    #[cfg(feature = "smoothing")]
+    write!(writer, ", smooth: {} {}%", settings.sampling.burst, settings.sampling.weight)?;
+
+    #[cfg(feature = "statistics")]
+    write!(writer, ", stats: {} h {} h", settings.statistics_windows.0, settings.statistics_windows.1)?;
+
+    writeln!(writer)
+}
+
+fn chip_name(id: u8) -> &'static str {
+    match id {
+        0x60 => "BME280",
+        0x58 => "BMP280",
+        _ => "unknown",
+    }
+}
+
+#[cfg(feature = "alarms")]
+fn alarm_name(kind: AlarmKind) -> &'static str {
+    match kind {
+        AlarmKind::TemperatureHigh => "temperature high",
+        AlarmKind::TemperatureLow => "temperature low",
+        AlarmKind::HumidityHigh => "humidity high",
+        AlarmKind::HumidityLow => "humidity low",
+        AlarmKind::PressureDrop => "storm",
+    }
+}
+
+#[cfg(feature = "alarms")]
+fn alarm_field(kind: AlarmKind) -> &'static str {
+    match kind {
+        AlarmKind::TemperatureHigh => "temperature_high",
+        AlarmKind::TemperatureLow => "temperature_low",
+        AlarmKind::HumidityHigh => "humidity_high",
+        AlarmKind::HumidityLow => "humidity_low",
+        AlarmKind::PressureDrop => "storm",
+    }
+}
+
+// Unit written after alarm value, with separating space where text output needs it.
+#[cfg(feature = "alarms")]
+fn alarm_unit(kind: AlarmKind) -> &'static str {
+    match kind {
+        AlarmKind::TemperatureHigh | AlarmKind::TemperatureLow => " C",
+        AlarmKind::HumidityHigh | AlarmKind::HumidityLow => "%",
+        AlarmKind::PressureDrop => " hPa/3h",
+    }
+}
+
+fn temperature_symbol(unit: TemperatureUnit) -> &'static str {
+    match unit {
+        TemperatureUnit::Celsius => "C",
+        TemperatureUnit::Fahrenheit => "F",
+    }
+}
+
+fn temperature_field(unit: TemperatureUnit) -> &'static str {
+    match unit {
+        TemperatureUnit::Celsius => "c",
+        TemperatureUnit::Fahrenheit => "f",
+    }
+}
+
+fn pressure_symbol(unit: PressureUnit) -> &'static str {
+    match unit {
+        PressureUnit::Pascal => "Pa",
+        PressureUnit::Hectopascal => "hPa",
+        PressureUnit::MillimetreMercury => "mmHg",
+    }
+}
+
+fn pressure_field(unit: PressureUnit) -> &'static str {
+    match unit {
+        PressureUnit::Pascal => "pa",
+        PressureUnit::Hectopascal => "hpa",
+        PressureUnit::MillimetreMercury => "mmhg",
+    }
+}
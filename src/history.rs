@@ -0,0 +1,114 @@
+use crate::sensor::Measurement;
+use telemetry::Flags;
+
+const HISTORY_SIZE: usize = 200;
+
+// Measurement packed to 11 bytes: temperature in 0.01 C, pressure in 2 Pa, humidity in 0.01% and quality flags.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct Sample {
+    time: u32,
+    temperature: i16,
+    pressure: u16,
+    humidity: u16,
+    flags: u8,
+}
+
+impl Sample {
+    pub const fn empty() -> Sample {
+        Sample {
+            time: 0,
+            temperature: 0,
+            pressure: 0,
+            humidity: 0,
+            flags: 0,
+        }
+    }
+
+    pub fn new(time: u32, measurement: &Measurement) -> Sample {
+        Sample {
+            time,
+            temperature: measurement.temperature.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            pressure: (measurement.pressure / 512).min(u16::MAX as u32) as u16,
+            humidity: (100 * measurement.humidity / 1024) as u16,
+            flags: measurement.flags.0,
+        }
+    }
+
+    pub fn time(&self) -> u32 {
+        self.time
+    }
+
+    pub fn measurement(&self) -> Measurement {
+        Measurement {
+            temperature: self.temperature as i32,
+            pressure: self.pressure as u32 * 512,
+            humidity: self.humidity as u32 * 1024 / 100,
+            flags: Flags(self.flags),
+        }
+    }
+}
+
+// Ring buffer of last measurements, oldest sample overwritten when buffer is full. Samples are numbered from 1 in
+// order they were pushed, numbers continue after buffer is cleared.
+pub struct History {
+    samples: [Sample; HISTORY_SIZE],
+    start: usize,
+    length: usize,
+    sequence: u32,
+}
+
+impl History {
+    pub const fn new() -> History {
+        History {
+            samples: [Sample::empty(); HISTORY_SIZE],
+            start: 0,
+            length: 0,
+            sequence: 0,
+        }
+    }
+
+    // Sequence number of last pushed sample.
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn last(&self) -> Option<&Sample> {
+        match self.length {
+            0 => None,
+            length => Some(&self.samples[(self.start + length - 1) % HISTORY_SIZE]),
+        }
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        self.sequence = self.sequence.wrapping_add(1);
+
+        if self.length < HISTORY_SIZE {
+            self.samples[(self.start + self.length) % HISTORY_SIZE] = sample;
+            self.length += 1;
+        } else {
+            self.samples[self.start] = sample;
+            self.start = (self.start + 1) % HISTORY_SIZE;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.length = 0;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Sample)> {
+        let first = self.sequence.wrapping_sub(self.length as u32).wrapping_add(1);
+
+        (0..self.length).map(move |index| {
+            (
+                first.wrapping_add(index as u32),
+                &self.samples[(self.start + index) % HISTORY_SIZE],
+            )
+        })
+    }
+}
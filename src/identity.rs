@@ -0,0 +1,44 @@
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::ptr::read_volatile;
+
+const UID_ADDRESS: usize = 0x1FFF_7590;
+
+pub const FIRMWARE_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_HASH: &str = env!("GIT_HASH"); // Set by `build.rs`.
+
+// 96-bit unique device ID programmed during manufacturing, used as sensor ID in machine-readable output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceId([u32; 3]);
+
+impl DeviceId {
+    #[allow(unsafe_code)]
+    pub fn read() -> DeviceId {
+        let words = UID_ADDRESS as *const u32;
+
+        DeviceId(unsafe {
+            [
+                read_volatile(words),
+                read_volatile(words.add(1)),
+                read_volatile(words.add(2)),
+            ]
+        })
+    }
+
+    #[cfg(feature = "binary")]
+    pub fn to_bytes(self) -> [u8; 12] {
+        let mut bytes = [0; 12];
+        bytes[0..4].copy_from_slice(&self.0[0].to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.0[1].to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.0[2].to_le_bytes());
+
+        bytes
+    }
+}
+
+impl Display for DeviceId {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:08x}{:08x}{:08x}", self.0[2], self.0[1], self.0[0])
+    }
+}
@@ -3,42 +3,95 @@
 #![no_main]
 #![no_std]
 
-use bme280::Bme280;
-use bme280::HumidityExt;
-use bme280::HumiditySampling;
-use bme280::PressureExt;
-use bme280::PressureSampling;
-use bme280::SensorMode;
-use bme280::TemperatireSampling;
-use bme280::TemperatureExt;
+// Types of settings and RTIC resources are kept in firmware without the subsystem.
+#[cfg_attr(not(feature = "alarms"), allow(dead_code))]
+mod alarm;
+#[cfg_attr(not(feature = "bluetooth"), allow(dead_code))]
+mod bluetooth;
+mod clock;
+mod format;
+mod history;
+mod identity;
+mod monotonic;
+mod output;
+mod panic;
+mod power;
+#[cfg(feature = "modbus")]
+mod registers;
+mod reset;
+mod sensor;
+mod settings;
+#[cfg_attr(not(feature = "statistics"), allow(dead_code))]
+mod statistics;
+mod storage;
+#[cfg(feature = "forecast")]
+mod weather;
+
+use crate::alarm::Alarms;
+use crate::clock::Clock;
+#[cfg(feature = "modbus")]
+use crate::clock::Timestamp;
+use crate::history::History;
+use crate::history::Sample;
+use crate::monotonic::RtcTicks;
+use crate::monotonic::Seconds;
+use crate::output::OutputBuffer;
+use crate::output::OutputWriter;
+use crate::power::Power;
+#[cfg(feature = "modbus")]
+use crate::registers::ModbusRegisters;
+use crate::reset::ResetCause;
+use crate::sensor::Sensor;
+use crate::sensor::SensorError;
+use crate::settings::Key;
+use crate::settings::Settings;
+#[cfg(feature = "modbus")]
+use crate::settings::KEYS;
+use crate::statistics::Statistics;
+use crate::storage::Storage;
 use core::fmt::Write;
-use core::panic::PanicInfo;
-use cortex_m::asm::wfi;
+#[cfg(feature = "modbus")]
+use core::mem::replace;
+use cortex_m::peripheral::SCB;
 use cortex_m::prelude::*;
 use cortex_m_rtic_macros::app;
-use stm32g0xx_hal::gpio::gpioa::PA11;
-use stm32g0xx_hal::gpio::gpioa::PA12;
+use nb::block;
 use stm32g0xx_hal::gpio::gpiob::PB0;
 use stm32g0xx_hal::gpio::GpioExt;
-use stm32g0xx_hal::gpio::OpenDrain;
 use stm32g0xx_hal::gpio::Output;
 use stm32g0xx_hal::gpio::PushPull;
-use stm32g0xx_hal::i2c::Config as I2CConfig;
-use stm32g0xx_hal::i2c::I2c;
-use stm32g0xx_hal::i2c::I2cExt;
+#[cfg(feature = "modbus")]
+use stm32g0xx_hal::hal::serial::Write as SerialWrite;
 use stm32g0xx_hal::prelude::OutputPin;
 use stm32g0xx_hal::prelude::PinState;
+use stm32g0xx_hal::rcc::Rcc;
 use stm32g0xx_hal::rcc::RccExt;
 use stm32g0xx_hal::serial::BasicConfig;
+use stm32g0xx_hal::serial::Rx;
 use stm32g0xx_hal::serial::SerialExt;
 use stm32g0xx_hal::serial::Tx;
-use stm32g0xx_hal::stm32::I2C2;
+#[cfg(feature = "modbus")]
+use stm32g0xx_hal::stm32::Interrupt;
+use stm32g0xx_hal::stm32::TIM16;
 use stm32g0xx_hal::stm32::TIM17;
 use stm32g0xx_hal::stm32::USART2;
 use stm32g0xx_hal::time::U32Ext;
-use stm32g0xx_hal::timer::delay::DelayExt;
 use stm32g0xx_hal::timer::Timer;
 use stm32g0xx_hal::timer::TimerExt;
+use stm32g0xx_hal::watchdog::IWDGExt;
+use stm32g0xx_hal::watchdog::IndependedWatchdog;
+#[cfg(feature = "alarms")]
+use telemetry::alarm::ALARM_KINDS;
+use telemetry::command::Command;
+use telemetry::command::CommandError;
+use telemetry::command::CommandReader;
+use telemetry::command::PowerMode;
+use telemetry::modbus;
+use telemetry::modbus::FrameBuffer;
+#[cfg(feature = "modbus")]
+use telemetry::modbus::MAX_FRAME_SIZE;
+
+type Timer16 = Timer<TIM16>;
 
 type Timer17 = Timer<TIM17>;
 
@@ -46,26 +99,67 @@ type LedPin = PB0<Output<PushPull>>;
 
 type UsartTx = Tx<USART2, BasicConfig>;
 
-type I2C = I2c<I2C2, PA12<Output<OpenDrain>>, PA11<Output<OpenDrain>>>;
+type UsartRx = Rx<USART2, BasicConfig>;
 
-#[app(device = stm32g0xx_hal::stm32, peripherals = true)]
+const LINE_SIZE: usize = 256; // Longest line of history output, statistics window or info.
+#[cfg(feature = "alarms")]
+const BLINK_STEP_US: u32 = 125_000; // Step of alarm LED patterns.
+const WATCHDOG_TIMEOUT: u32 = 4; // Seconds, must be greater than tick and measurement time.
+
+#[app(device = stm32g0xx_hal::stm32, peripherals = true, dispatchers = [ SPI1 ])]
 mod app {
     use super::*;
 
+    #[monotonic(binds = RTC_TAMP, default = true)]
+    type Ticks = RtcTicks;
+
+    // Software tasks have priority 1, USART and frame timer interrupts have priority 2, so output is sent and
+    // requests are received while tasks are running.
     #[shared]
-    struct Shared {}
+    struct Shared {
+        settings: Settings,
+        output: OutputBuffer,
+        watchdog: IndependedWatchdog,
+        watchdog_lease: Option<u32>, // Second of monotonic time until which idle feeds watchdog.
+        frame_timer: Timer17,
+        modbus_frame: FrameBuffer,
+        modbus_request: FrameBuffer, // Complete frame waiting for `process_request`.
+        alarms: Alarms,
+        #[lock_free]
+        led: LedPin,
+        #[lock_free]
+        blink_timer: Timer16,
+        #[lock_free]
+        sensor: Sensor,
+        #[lock_free]
+        history: History,
+        #[lock_free]
+        statistics: Statistics,
+        #[lock_free]
+        clock: Clock,
+        #[lock_free]
+        storage: Storage,
+        #[lock_free]
+        next_measurement: Option<measure_periodic::SpawnHandle>,
+    }
 
     #[local]
     struct Local {
-        timer: Timer17,
-        led: LedPin,
-        usart_tx: UsartTx,
-        i2c: I2C,
-        bme280: Bme280,
+        rcc: Rcc,
+        usart_rx: UsartRx,
+        frame_gap: u32,
+        power: Power,
     }
 
     #[init]
     fn init(context: init::Context) -> (Shared, Local, init::Monotonics) {
+        let reset_cause = ResetCause::read(&context.device.RCC);
+        let power = Power::new(context.core.SCB, context.device.PWR, &context.device.RCC);
+        // Bluetooth provisioning stores updated settings.
+        #[cfg_attr(not(feature = "bluetooth"), allow(unused_mut))]
+        let mut storage = Storage::new(context.device.FLASH);
+        #[cfg_attr(not(feature = "bluetooth"), allow(unused_mut))]
+        let mut settings = storage.load();
         let mut rcc = context.device.RCC.constrain();
         let gpioa = context.device.GPIOA.split(&mut rcc);
         let gpiob = context.device.GPIOB.split(&mut rcc);
@@ -74,97 +168,594 @@ mod app {
         let rx = gpioa.pa3;
         let sda = gpioa.pa12.into_open_drain_output_in_state(PinState::High);
         let scl = gpioa.pa11.into_open_drain_output_in_state(PinState::High);
-        let (mut usart_tx, _usart_rx) = context
+        let (mut usart_tx, mut usart_rx) = context
             .device
             .USART2
             .usart(
                 tx,
                 rx,
-                BasicConfig::default().baudrate(9_600.bps()),
+                BasicConfig::default().baudrate(settings.baud_rate.bps()),
                 &mut rcc,
             )
             .unwrap()
             .split();
-        let mut i2c = context
-            .device
-            .I2C2
-            .i2c(sda, scl, I2CConfig::new(400.khz()), &mut rcc);
-        let bme280 = Bme280::address_low(&mut i2c).expect("Failed to initialize BME280 sensor");
-        bme280
-            .reset(&mut i2c)
-            .expect("Failed to reset BME280 sensor");
-        bme280
-            .sampling(
-                &mut i2c,
-                HumiditySampling::Sampling16,
-                TemperatireSampling::Sampling16,
-                PressureSampling::Sampling16,
-                SensorMode::Normal,
-            )
-            .expect("Failed to set sampling for BME280 sensor");
 
-        let mut delay = context.device.TIM17.delay(&mut rcc);
-        delay.delay(100.ms()); // Wait for BME280 first measurement.
+        // Started while alarm is active, interrupt advances LED pattern. Before that it times Bluetooth responses.
+        let mut blink_timer = context.device.TIM16.timer(&mut rcc);
+
+        // Module is provisioned at start, when it is not connected yet. Watchdog is not started, provisioning
+        // takes a second per command.
+        #[cfg(feature = "bluetooth")]
+        if settings.bluetooth.pending {
+            let baud_rate = settings.baud_rate;
+            let result = bluetooth::provision(
+                &mut usart_tx,
+                &mut usart_rx,
+                &mut blink_timer,
+                &rcc,
+                baud_rate,
+                &settings.bluetooth,
+            );
+            settings.bluetooth.pending = false;
 
-        let mut timer = delay.release().timer(&mut rcc);
-        timer.start(60.seconds());
-        timer.listen();
+            if result.is_ok() {
+                settings.baud_rate = settings.bluetooth.baud_rate;
+            }
+
+            let _ = storage.store(&settings);
+
+            if result.is_err() {
+                let _ = writeln!(usart_tx, "Error: Bluetooth provisioning failed.");
+            } else if settings.baud_rate != baud_rate {
+                // USART is configured with new baud rate after reset.
+                SCB::sys_reset();
+            }
+        }
 
-        let _ = writeln!(usart_tx, "Ready.");
+        usart_rx.listen();
+
+        let mut sensor = Sensor::new(
+            context.device.I2C2,
+            sda,
+            scl,
+            settings.sampling,
+            &mut rcc,
+        );
+        let chip = sensor.probe(&mut rcc).ok();
+
+        let clock = Clock::new(context.device.RTC, &mut rcc);
+        let ticks = RtcTicks::new(&clock);
+
+        // Started by received bytes, interrupt marks end of Modbus frame.
+        let mut frame_timer = context.device.TIM17.timer(&mut rcc);
+        frame_timer.listen();
+
+        blink_timer.listen();
+
+        let mut watchdog = context.device.IWDG.constrain();
+        watchdog.start(WATCHDOG_TIMEOUT.seconds());
+
+        // Text on Modbus line would be received by other slaves as damaged frames.
+        if settings.modbus_address == 0 {
+            let _ = writeln!(usart_tx, "Ready. Reset cause: {}.", reset_cause);
+
+            panic::report_last_panic(&mut usart_tx);
+
+            let _ = format::write_identity(&mut usart_tx, chip);
+            let _ = format::write_settings(&mut usart_tx, &settings);
+            let _ = format::write_header(&mut usart_tx, &settings);
+        }
+
+        let _ = measure_periodic::spawn();
+
+        let mut statistics = Statistics::new();
+
+        if cfg!(feature = "statistics") {
+            statistics.set_windows(settings.statistics_windows);
+        }
 
         (
-            Shared {},
-            Local {
-                timer,
+            Shared {
+                settings,
+                output: OutputBuffer::new(usart_tx),
+                watchdog,
+                watchdog_lease: None,
+                frame_timer,
+                modbus_frame: FrameBuffer::new(),
+                modbus_request: FrameBuffer::new(),
+                alarms: Alarms::new(),
                 led,
-                usart_tx,
-                i2c,
-                bme280,
+                blink_timer,
+                sensor,
+                history: History::new(),
+                statistics,
+                clock,
+                storage,
+                next_measurement: None,
+            },
+            Local {
+                rcc,
+                usart_rx,
+                frame_gap: modbus::frame_gap_us(settings.baud_rate),
+                power,
             },
-            init::Monotonics(),
+            init::Monotonics(ticks),
         )
     }
 
-    #[idle(local = [ led, usart_tx, i2c, bme280 ])]
-    fn idle(context: idle::Context) -> ! {
+    #[idle(local = [ power ], shared = [ settings, output, watchdog, watchdog_lease, alarms ])]
+    fn idle(mut context: idle::Context) -> ! {
+        let mut output = OutputWriter(context.shared.output);
+
         loop {
-            let _ = context.local.led.set_low();
-            let temperature = context
-                .local
-                .bme280
-                .temperature(context.local.i2c)
-                .expect("Failed to read temperature")
-                .c();
-            let pressure = context
-                .local
-                .bme280
-                .pressure(context.local.i2c)
-                .expect("Failed to read pressure")
-                .mmhg();
-            let humidity = context
-                .local
-                .bme280
-                .humidity(context.local.i2c)
-                .expect("Failed to read humidity")
-                .percent();
-            let _ = writeln!(
-                context.local.usart_tx,
-                "T: {}.{:02} C, P: {}.{:01} mmHg, H: {}.{:03}%",
-                temperature.0, temperature.1, pressure.0, pressure.1, humidity.0, humidity.1,
-            );
-            let _ = context.local.led.set_high();
+            // Interval between measurements is longer than watchdog timeout, successful measurement gives lease until
+            // next one is due. Failed, hung or missed measurement lets watchdog reset MCU.
+            let now = monotonics::now().duration_since_epoch().to_secs();
+
+            if context.shared.watchdog_lease.lock(|lease| lease.is_some_and(|lease| now <= lease)) {
+                context.shared.watchdog.lock(|watchdog| watchdog.feed());
+            }
+
+            let settings = context.shared.settings.lock(|settings| *settings);
+            let alarm_active = context.shared.alarms.lock(|alarms| alarms.active_mask() != 0);
 
-            wfi();
+            // Frame timer, LED pattern timer and USART do not run in STOP mode, buffered output is sent before
+            // stopping.
+            if settings.modbus_address == 0 && settings.power_mode == PowerMode::Stop && !alarm_active {
+                let _ = block!(output.flush());
+
+                context.local.power.sleep(PowerMode::Stop);
+            } else {
+                context.local.power.sleep(PowerMode::Sleep);
+            }
         }
     }
 
-    #[task(binds = TIM17, local = [ timer ])]
-    fn timer_tick(context: timer_tick::Context) {
-        context.local.timer.clear_irq();
+    #[task(shared = [ settings, next_measurement ])]
+    fn measure_periodic(mut context: measure_periodic::Context) {
+        let _ = measure::spawn();
+
+        let interval = context.shared.settings.lock(|settings| settings.interval);
+        *context.shared.next_measurement = measure_periodic::spawn_after(Seconds::secs(interval)).ok();
     }
-}
 
-#[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
-    loop {}
+    // Periodic measurement and `READ` command may be spawned together.
+    #[task(
+        capacity = 2,
+        local = [ rcc ],
+        shared = [
+            settings,
+            output,
+            watchdog,
+            watchdog_lease,
+            alarms,
+            led,
+            blink_timer,
+            sensor,
+            history,
+            statistics,
+            clock,
+        ]
+    )]
+    fn measure(mut context: measure::Context) {
+        let settings = context.shared.settings.lock(|settings| *settings);
+        let timestamp = context.shared.clock.timestamp();
+        let mut output = OutputWriter(context.shared.output);
+        let sensor = context.shared.sensor;
+        let _ = context.shared.led.set_low();
+
+        let result = sensor.measure(context.local.rcc, timestamp);
+        let now = monotonics::now().duration_since_epoch().to_secs();
+
+        if result.is_ok() {
+            context.shared.watchdog.lock(|watchdog| watchdog.feed());
+        }
+
+        context
+            .shared
+            .watchdog_lease
+            .lock(|lease| *lease = result.is_ok().then_some(now + settings.interval));
+
+        match result {
+            Ok(measurement) => {
+                let history = context.shared.history;
+                #[cfg(feature = "alarms")]
+                let pressure_drop = alarm::pressure_drop(history, timestamp, measurement.pressure);
+                #[cfg(feature = "forecast")]
+                let weather = weather::weather(history, timestamp, measurement.pressure, settings.altitude);
+
+                history.push(Sample::new(timestamp, &measurement));
+                #[cfg(feature = "statistics")]
+                context.shared.statistics.push(timestamp, &measurement);
+
+                // Modbus master reads measurements from input registers.
+                if settings.modbus_address == 0 {
+                    let _ = format::write_measurement(
+                        &mut output,
+                        history.sequence(),
+                        timestamp,
+                        &measurement,
+                        #[cfg(feature = "forecast")]
+                        weather.as_ref(),
+                        &settings,
+                    );
+                }
+
+                #[cfg(feature = "alarms")]
+                {
+                    let humidity = ((100 * measurement.humidity + 512) / 1_024) as i32;
+                    let values = [
+                        Some(measurement.temperature),
+                        Some(measurement.temperature),
+                        Some(humidity),
+                        Some(humidity),
+                        pressure_drop,
+                    ];
+
+                    let (was_active, active) = context.shared.alarms.lock(|alarms| {
+                        let was_active = alarms.active_mask() != 0;
+
+                        for (kind, value) in ALARM_KINDS.iter().zip(values) {
+                            if let Some(event) = alarms.update(&settings.alarms, *kind, value) {
+                                if settings.modbus_address == 0 {
+                                    let _ = format::write_alarm(&mut output, &event, timestamp, &settings);
+                                }
+                            }
+                        }
+
+                        (was_active, alarms.active_mask() != 0)
+                    });
+
+                    if active && !was_active {
+                        context.shared.blink_timer.start(BLINK_STEP_US.us());
+                    }
+                }
+            }
+            Err(_) if settings.modbus_address != 0 => {}
+            Err(SensorError::Rejected) => {
+                let _ = writeln!(output, "Sensor error, reading rejected (rejected: {}).", sensor.rejected());
+            }
+            Err(_) => {
+                let _ = writeln!(
+                    output,
+                    "Sensor error, I2C bus recovered (recoveries: {}).",
+                    sensor.recoveries(),
+                );
+            }
+        }
+
+        let _ = context.shared.led.set_high();
+    }
+
+    // Shows patterns of active alarms, LED is on at low level.
+    #[cfg(feature = "alarms")]
+    #[task(binds = TIM16, shared = [ alarms, led, blink_timer ])]
+    fn blink(mut context: blink::Context) {
+        context.shared.blink_timer.clear_irq();
+
+        let (on, active) = context
+            .shared
+            .alarms
+            .lock(|alarms| (alarms.pattern_step(), alarms.active_mask() != 0));
+
+        if on {
+            let _ = context.shared.led.set_low();
+        } else {
+            let _ = context.shared.led.set_high();
+        }
+
+        if !active {
+            context.shared.blink_timer.pause();
+        }
+    }
+
+    #[task(
+        capacity = 4,
+        shared = [
+            settings,
+            output,
+            watchdog,
+            watchdog_lease,
+            alarms,
+            sensor,
+            history,
+            statistics,
+            clock,
+            storage,
+            next_measurement,
+        ]
+    )]
+    fn execute_command(mut context: execute_command::Context, command: Result<Command, CommandError>) {
+        let settings = context.shared.settings.lock(|settings| *settings);
+        let mut output = OutputWriter(context.shared.output);
+
+        match command {
+            Ok(Command::Read) => {
+                let _ = measure::spawn();
+            }
+            Ok(Command::History) => {
+                let history = context.shared.history;
+                let _ = writeln!(output, "History: {} samples.", history.len());
+
+                let _ = format::write_header(&mut output, &settings);
+
+                for (sequence, sample) in history.iter() {
+                    output.wait_available(LINE_SIZE);
+
+                    let _ = format::write_measurement(
+                        &mut output,
+                        sequence,
+                        sample.time(),
+                        &sample.measurement(),
+                        #[cfg(feature = "forecast")]
+                        None,
+                        &settings,
+                    );
+
+                    // Dump takes several seconds at low baud rates.
+                    context.shared.watchdog.lock(|watchdog| watchdog.feed());
+                }
+
+                let _ = writeln!(output, "OK.");
+            }
+            #[cfg(feature = "statistics")]
+            Ok(Command::Statistics) => {
+                let statistics = context.shared.statistics;
+                let timestamp = context.shared.clock.timestamp();
+
+                // Each window takes most of output buffer.
+                output.wait_available(LINE_SIZE);
+                let _ = format::write_statistics(&mut output, &statistics.short, timestamp, &settings);
+                output.wait_available(LINE_SIZE);
+                let _ = format::write_statistics(&mut output, &statistics.long, timestamp, &settings);
+            }
+            #[cfg(feature = "statistics")]
+            Ok(Command::ClearStatistics) => {
+                context.shared.statistics.clear();
+                let _ = writeln!(output, "OK.");
+            }
+            Ok(Command::ClearHistory) => {
+                context.shared.history.clear();
+
+                let _ = writeln!(output, "OK.");
+            }
+            Ok(Command::Time(Some(date_time))) => {
+                context.shared.clock.set(&date_time);
+
+                let _ = writeln!(output, "OK.");
+            }
+            Ok(Command::Time(None)) => {
+                let _ = writeln!(output, "Time: {}", context.shared.clock.now());
+            }
+            Ok(Command::Reset) => {
+                let _ = writeln!(output, "OK.");
+                let _ = block!(output.flush());
+
+                SCB::sys_reset();
+            }
+            #[cfg(feature = "alarms")]
+            Ok(Command::Alarms) => {
+                context
+                    .shared
+                    .alarms
+                    .lock(|alarms| format::write_alarms(&mut output, alarms, &settings))
+                    .ok();
+            }
+            Ok(Command::Info) => {
+                let uptime = monotonics::now().duration_since_epoch().to_secs();
+
+                output.wait_available(LINE_SIZE);
+                let _ = format::write_identity(&mut output, context.shared.sensor.chip());
+                output.wait_available(LINE_SIZE);
+                let _ = format::write_settings(&mut output, &settings);
+                output.wait_available(LINE_SIZE);
+                let dropped = output.dropped();
+                let _ = writeln!(
+                    output,
+                    "Uptime: {}, recoveries: {}, rejected readings: {}, dropped output bytes: {}",
+                    format::Uptime(uptime),
+                    context.shared.sensor.recoveries(),
+                    context.shared.sensor.rejected(),
+                    dropped,
+                );
+            }
+            Ok(command) => {
+                let mut updated = settings;
+
+                if !updated.update(command) {
+                    let _ = writeln!(output, "Error: {}.", CommandError::InvalidArgument);
+
+                    return;
+                }
+
+                context.shared.settings.lock(|settings| *settings = updated);
+
+                context.shared.watchdog_lease.lock(|lease| {
+                    apply_settings(
+                        &settings,
+                        &updated,
+                        context.shared.sensor,
+                        context.shared.next_measurement,
+                        lease,
+                    )
+                });
+
+                #[cfg(feature = "statistics")]
+                context.shared.statistics.set_windows(updated.statistics_windows);
+
+                let bluetooth = matches!(
+                    command,
+                    Command::Baud(..) | Command::Bluetooth(..) | Command::BluetoothName(..) | Command::BluetoothPin(..)
+                );
+
+                // Connected module would send AT commands to connected device, so it is provisioned at start.
+                match context.shared.storage.store(&updated) {
+                    Ok(()) if cfg!(feature = "bluetooth") && bluetooth && updated.bluetooth.pending => {
+                        let _ = writeln!(output, "OK, module is provisioned after disconnection and reset.");
+                    }
+                    Ok(()) if updated.baud_rate != settings.baud_rate => {
+                        let _ = writeln!(output, "OK, baud rate is changed after reset.");
+                    }
+                    Ok(()) => {
+                        let _ = writeln!(output, "OK.");
+                    }
+                    Err(_) => {
+                        let _ = writeln!(output, "Error: failed to store settings.");
+                    }
+                }
+
+                // CSV column names depend on units.
+                if let Command::Format(..) | Command::Units(..) = command {
+                    let _ = format::write_header(&mut output, &updated);
+                }
+            }
+            Err(error) => {
+                let _ = writeln!(output, "Error: {}.", error);
+            }
+        }
+    }
+
+    // Pended by frame timer, runs with priority of software tasks. Unlike software task it can be configured out.
+    #[cfg(feature = "modbus")]
+    #[task(
+        binds = SPI2,
+        shared = [
+            settings,
+            output,
+            modbus_request,
+            alarms,
+            sensor,
+            history,
+            statistics,
+            clock,
+            storage,
+            next_measurement,
+            watchdog_lease,
+        ]
+    )]
+    fn process_request(mut context: process_request::Context) {
+        let request = context
+            .shared
+            .modbus_request
+            .lock(|request| replace(request, FrameBuffer::new()));
+        let request = match request.frame() {
+            Some(request) => request,
+            None => return,
+        };
+        let settings = context.shared.settings.lock(|settings| *settings);
+        let clock_set = Timestamp(context.shared.clock.timestamp()).is_set();
+        let alarms = context.shared.alarms.lock(|alarms| alarms.active_mask());
+        let mut registers = ModbusRegisters::new(
+            settings,
+            context.shared.history,
+            context.shared.sensor.recoveries(),
+            clock_set,
+            alarms,
+        );
+        let mut response = [0; MAX_FRAME_SIZE];
+
+        if let Some(length) =
+            modbus::process(settings.modbus_address, request, &mut registers, &mut response)
+        {
+            let mut output = OutputWriter(context.shared.output);
+
+            for byte in &response[..length] {
+                let _ = output.write(*byte);
+            }
+        }
+
+        let updated = registers.settings();
+
+        if KEYS.iter().any(|key| updated.get(*key) != settings.get(*key)) {
+            context.shared.settings.lock(|settings| *settings = updated);
+
+            context.shared.watchdog_lease.lock(|lease| {
+                apply_settings(
+                    &settings,
+                    &updated,
+                    context.shared.sensor,
+                    context.shared.next_measurement,
+                    lease,
+                )
+            });
+
+            #[cfg(feature = "statistics")]
+            context.shared.statistics.set_windows(updated.statistics_windows);
+
+            // Response is already sent, failure is reported by stored values read after reset.
+            let _ = context.shared.storage.store(&updated);
+        }
+    }
+
+    #[task(
+        binds = USART2,
+        priority = 2,
+        local = [ usart_rx, frame_gap, reader: CommandReader = CommandReader::new() ],
+        shared = [ settings, frame_timer, modbus_frame, output ]
+    )]
+    fn usart(mut context: usart::Context) {
+        #[cfg(feature = "modbus")]
+        let modbus_address = context.shared.settings.lock(|settings| settings.modbus_address);
+        #[cfg(feature = "modbus")]
+        let frame_gap = *context.local.frame_gap;
+
+        while let Ok(byte) = context.local.usart_rx.read() {
+            #[cfg(feature = "modbus")]
+            if modbus_address != 0 {
+                context.shared.modbus_frame.lock(|frame| frame.push(byte));
+                context.shared.frame_timer.lock(|timer| timer.start(frame_gap.us()));
+
+                continue;
+            }
+
+            if let Some(result) = context.local.reader.push(byte) {
+                // Commands received while queue is full are dropped.
+                let _ = execute_command::spawn(result);
+            }
+        }
+
+        context.shared.output.lock(|output| output.drain());
+    }
+
+    #[cfg(feature = "modbus")]
+    #[task(binds = TIM17, priority = 2, shared = [ frame_timer, modbus_frame, modbus_request ])]
+    fn frame_timeout(mut context: frame_timeout::Context) {
+        context.shared.frame_timer.lock(|timer| {
+            timer.clear_irq();
+            timer.pause();
+        });
+
+        let frame = context
+            .shared
+            .modbus_frame
+            .lock(|frame| replace(frame, FrameBuffer::new()));
+        context.shared.modbus_request.lock(|request| *request = frame);
+        rtic::pend(Interrupt::SPI2);
+    }
+
+    // Applies changes which take effect without reset.
+    fn apply_settings(
+        settings: &Settings,
+        updated: &Settings,
+        sensor: &mut Sensor,
+        next_measurement: &mut Option<measure_periodic::SpawnHandle>,
+        watchdog_lease: &mut Option<u32>,
+    ) {
+        if updated.get(Key::Sampling) != settings.get(Key::Sampling)
+            || updated.get(Key::Smoothing) != settings.get(Key::Smoothing)
+        {
+            sensor.set_sampling(updated.sampling);
+        }
+
+        // New interval is counted from now, lease of watchdog is extended to next measurement.
+        if updated.interval != settings.interval {
+            *next_measurement = next_measurement
+                .take()
+                .and_then(|handle| handle.reschedule_after(Seconds::secs(updated.interval)).ok());
+
+            if watchdog_lease.is_some() {
+                *watchdog_lease = Some(monotonics::now().duration_since_epoch().to_secs() + updated.interval);
+            }
+        }
+    }
 }
@@ -0,0 +1,87 @@
+use crate::clock::Clock;
+use fugit::TimerDurationU32;
+use fugit::TimerInstantU32;
+use rtic::Monotonic;
+use stm32g0xx_hal::stm32::RTC;
+
+const WAKEUP_CLOCK_1HZ: u8 = 0b100;
+
+pub type Seconds = TimerDurationU32<1>;
+
+// Monotonic timer counting RTC wakeup interrupts, unlike SysTick and TIM17 it keeps running in STOP mode. Wakeup
+// interrupt is generated every second, so compare value is not programmed and due tasks are checked on each tick.
+pub struct RtcTicks {
+    ticks: u32,
+}
+
+impl RtcTicks {
+    // Clock enables RTC.
+    #[allow(unsafe_code)]
+    pub fn new(_clock: &Clock) -> RtcTicks {
+        let rtc = unsafe { &*RTC::ptr() };
+
+        rtc.wpr.write(|w| unsafe { w.key().bits(0xCA) });
+        rtc.wpr.write(|w| unsafe { w.key().bits(0x53) });
+        rtc.cr.modify(|_, w| w.wute().clear_bit());
+        while rtc.icsr.read().wutwf().bit_is_clear() {}
+        rtc.wutr.write(|w| unsafe { w.wut().bits(0) });
+        rtc.cr.modify(|_, w| {
+            unsafe { w.wucksel().bits(WAKEUP_CLOCK_1HZ) }
+                .wutie()
+                .set_bit()
+                .wute()
+                .set_bit()
+        });
+        rtc.wpr.write(|w| unsafe { w.key().bits(0xFF) });
+
+        RtcTicks { ticks: 0 }
+    }
+
+    #[allow(unsafe_code)]
+    fn tick_pending(&self) -> bool {
+        let rtc = unsafe { &*RTC::ptr() };
+
+        rtc.sr.read().wutf().bit_is_set()
+    }
+
+    #[allow(unsafe_code)]
+    fn clear_tick(&mut self) {
+        let rtc = unsafe { &*RTC::ptr() };
+
+        rtc.scr.write(|w| w.cwutf().set_bit());
+    }
+}
+
+impl Monotonic for RtcTicks {
+    type Instant = TimerInstantU32<1>;
+    type Duration = TimerDurationU32<1>;
+
+    const DISABLE_INTERRUPT_ON_EMPTY_QUEUE: bool = false;
+
+    fn now(&mut self) -> Self::Instant {
+        // Pending tick is counted before interrupt handler clears it.
+        let pending = self.tick_pending() as u32;
+
+        Self::Instant::from_ticks(self.ticks.wrapping_add(pending))
+    }
+
+    fn zero() -> Self::Instant {
+        Self::Instant::from_ticks(0)
+    }
+
+    #[allow(unsafe_code)]
+    unsafe fn reset(&mut self) {
+        self.ticks = 0;
+    }
+
+    fn set_compare(&mut self, _instant: Self::Instant) {}
+
+    fn clear_compare_flag(&mut self) {}
+
+    fn on_interrupt(&mut self) {
+        if self.tick_pending() {
+            self.clear_tick();
+            self.ticks = self.ticks.wrapping_add(1);
+        }
+    }
+}
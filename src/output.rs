@@ -0,0 +1,125 @@
+use crate::UsartTx;
+use core::convert::Infallible;
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+use rtic::Mutex;
+use stm32g0xx_hal::hal::serial::Write as SerialWrite;
+
+const BUFFER_SIZE: usize = 512;
+
+// Ring buffer of bytes written to USART by TXE interrupt. When buffer is full new bytes are dropped and counted.
+pub struct OutputBuffer {
+    tx: UsartTx,
+    buffer: [u8; BUFFER_SIZE],
+    start: usize,
+    length: usize,
+    dropped: u32,
+}
+
+impl OutputBuffer {
+    pub fn new(tx: UsartTx) -> OutputBuffer {
+        OutputBuffer {
+            tx,
+            buffer: [0; BUFFER_SIZE],
+            start: 0,
+            length: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) {
+        if self.length == BUFFER_SIZE {
+            self.dropped = self.dropped.wrapping_add(1);
+            return;
+        }
+
+        self.buffer[(self.start + self.length) % BUFFER_SIZE] = byte;
+        self.length += 1;
+
+        if self.length == 1 {
+            self.tx.listen();
+        }
+    }
+
+    // Moves bytes to USART while it accepts them, called from USART interrupt.
+    pub fn drain(&mut self) {
+        while self.length > 0 {
+            if self.tx.write(self.buffer[self.start]).is_err() {
+                return;
+            }
+
+            self.start = (self.start + 1) % BUFFER_SIZE;
+            self.length -= 1;
+        }
+
+        self.tx.unlisten();
+    }
+
+    // Completes when buffer is empty and last byte is shifted out.
+    pub fn flush(&mut self) -> nb::Result<(), Infallible> {
+        if self.length > 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.tx.flush().map_err(|_| nb::Error::WouldBlock)
+    }
+
+    pub fn available(&self) -> usize {
+        BUFFER_SIZE - self.length
+    }
+
+    // Number of bytes dropped since reset.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+// Text and binary output of formats, used as trait object so formats are not compiled again for writer of each task.
+pub trait OutputWrite: Write + SerialWrite<u8, Error = Infallible> {}
+
+impl<T> OutputWrite for T where T: Write + SerialWrite<u8, Error = Infallible> {}
+
+// Writes to shared output from tasks with lower priority than USART interrupt.
+pub struct OutputWriter<M>(pub M);
+
+impl<M> OutputWriter<M>
+where
+    M: Mutex<T = OutputBuffer>,
+{
+    // Waits for free space, long outputs (history) are written without dropping bytes.
+    pub fn wait_available(&mut self, count: usize) {
+        while self.0.lock(|output| output.available()) < count {}
+    }
+
+    pub fn dropped(&mut self) -> u32 {
+        self.0.lock(|output| output.dropped())
+    }
+}
+
+impl<M> Write for OutputWriter<M>
+where
+    M: Mutex<T = OutputBuffer>,
+{
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        self.0.lock(|output| s.bytes().for_each(|byte| output.push(byte)));
+
+        Ok(())
+    }
+}
+
+impl<M> SerialWrite<u8> for OutputWriter<M>
+where
+    M: Mutex<T = OutputBuffer>,
+{
+    type Error = Infallible;
+
+    fn write(&mut self, byte: u8) -> nb::Result<(), Infallible> {
+        self.0.lock(|output| output.push(byte));
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Infallible> {
+        self.0.lock(|output| output.flush())
+    }
+}
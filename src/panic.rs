@@ -0,0 +1,122 @@
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+use core::mem::MaybeUninit;
+use core::panic::PanicInfo;
+use core::ptr::addr_of_mut;
+use core::str::from_utf8;
+use cortex_m::asm::delay;
+use cortex_m::interrupt;
+use cortex_m::peripheral::SCB;
+use stm32g0xx_hal::stm32::Peripherals;
+use stm32g0xx_hal::stm32::GPIOB;
+use stm32g0xx_hal::stm32::USART2;
+
+const PANIC_MAGIC: u32 = 0x5041_4E43;
+const PANIC_MESSAGE_SIZE: usize = 120;
+const TRANSMIT_TIMEOUT: u32 = 10_000; // Status register polls per byte.
+const BLINK_COUNT: usize = 5;
+const BLINK_DELAY: u32 = 800_000; // CPU cycles, 50 ms at 16 MHz.
+
+#[repr(C)]
+struct PanicRecord {
+    magic: u32,
+    length: u32,
+    message: [u8; PANIC_MESSAGE_SIZE],
+}
+
+// Placed to RAM region reserved in `memory.x`, this region is not initialized on start up so record survives
+// software and watchdog resets.
+#[allow(unsafe_code)] // Section is declared `NOLOAD` in `memory.x`, record is accessed only through `panic_record`.
+#[link_section = ".panic"]
+static mut PANIC_RECORD: MaybeUninit<PanicRecord> = MaybeUninit::uninit();
+
+struct PanicWriter<'a> {
+    usart: Option<&'a USART2>,
+    record: &'a mut PanicRecord,
+}
+
+impl<'a> PanicWriter<'a> {
+    #[allow(unsafe_code)]
+    fn write_byte(&mut self, byte: u8) {
+        let length = self.record.length as usize;
+
+        if length < PANIC_MESSAGE_SIZE {
+            self.record.message[length] = byte;
+            self.record.length += 1;
+        }
+
+        if let Some(usart) = self.usart {
+            for _ in 0..TRANSMIT_TIMEOUT {
+                if usart.isr.read().txe().bit_is_set() {
+                    usart.tdr.write(|w| unsafe { w.bits(byte as u32) });
+
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Write for PanicWriter<'a> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        for byte in s.bytes() {
+            self.write_byte(byte);
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(unsafe_code)]
+fn panic_record() -> &'static mut PanicRecord {
+    unsafe { &mut *(addr_of_mut!(PANIC_RECORD) as *mut PanicRecord) }
+}
+
+// Writes message of panic before last reset (if any) and forgets it.
+pub fn report_last_panic<W>(writer: &mut W)
+where
+    W: Write,
+{
+    let record = panic_record();
+
+    if record.magic == PANIC_MAGIC {
+        let length = (record.length as usize).min(PANIC_MESSAGE_SIZE);
+        let message = match from_utf8(&record.message[..length]) {
+            Ok(message) => message,
+            Err(error) => from_utf8(&record.message[..error.valid_up_to()]).unwrap_or_default(),
+        };
+        let _ = writeln!(writer, "Last panic: {}", message.trim_end_matches('\n'));
+    }
+
+    record.magic = 0;
+}
+
+fn blink(gpiob: &GPIOB) {
+    for _ in 0..2 * BLINK_COUNT {
+        gpiob.odr.modify(|r, w| w.odr0().bit(!r.odr0().bit()));
+        delay(BLINK_DELAY);
+    }
+}
+
+#[panic_handler]
+#[allow(unsafe_code)]
+fn panic(info: &PanicInfo) -> ! {
+    interrupt::disable();
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let record = panic_record();
+    record.magic = PANIC_MAGIC;
+    record.length = 0;
+
+    let usart = if peripherals.USART2.cr1.read().ue().bit_is_set() {
+        Some(&peripherals.USART2)
+    } else {
+        None
+    };
+    let mut writer = PanicWriter { usart, record };
+    let _ = writeln!(writer, "{}", info);
+
+    blink(&peripherals.GPIOB);
+
+    SCB::sys_reset()
+}
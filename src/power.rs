@@ -0,0 +1,34 @@
+use cortex_m::asm::wfi;
+use cortex_m::peripheral::SCB;
+use stm32g0xx_hal::stm32::PWR;
+use stm32g0xx_hal::stm32::RCC;
+use telemetry::command::PowerMode;
+
+const LOW_POWER_MODE_STOP_1: u8 = 0b001;
+
+// Stop 1 mode stops core and peripheral clocks, SRAM and registers are retained, LSI keeps RTC and IWDG running.
+// MCU wakes up running from HSI16, which is also firmware system clock, so no clock configuration is restored.
+pub struct Power {
+    scb: SCB,
+    _pwr: PWR,
+}
+
+impl Power {
+    #[allow(unsafe_code)]
+    pub fn new(scb: SCB, pwr: PWR, rcc: &RCC) -> Power {
+        rcc.apbenr1.modify(|_, w| w.pwren().set_bit());
+        pwr.cr1.modify(|_, w| unsafe { w.lpms().bits(LOW_POWER_MODE_STOP_1) });
+
+        Power { scb, _pwr: pwr }
+    }
+
+    // Waits for interrupt in Sleep or Stop mode.
+    pub fn sleep(&mut self, mode: PowerMode) {
+        match mode {
+            PowerMode::Sleep => self.scb.clear_sleepdeep(),
+            PowerMode::Stop => self.scb.set_sleepdeep(),
+        }
+
+        wfi();
+    }
+}
@@ -0,0 +1,133 @@
+use crate::history::History;
+use crate::settings::Key;
+use crate::settings::Settings;
+use telemetry::modbus::Exception;
+use telemetry::modbus::Registers;
+
+const STATUS_MEASUREMENT: u16 = 0b0000_0001;
+const STATUS_CLOCK_SET: u16 = 0b0000_0010;
+
+// Input registers (function 04):
+//
+// | Address | Value                                         |
+// |---------|-----------------------------------------------|
+// | 0       | Temperature, signed, 0.01 C                   |
+// | 1       | Pressure, 0.1 hPa                             |
+// | 2       | Humidity, 0.01%                               |
+// | 3       | Status, see below                             |
+// | 4, 5    | Sequence number, high word first              |
+// | 6, 7    | Time of measurement, high word first          |
+// | 8       | Number of I2C bus recoveries                  |
+// | 9       | Active alarms, bit per `ALARM_KINDS` entry    |
+//
+// Status bits 0 - measurement available, 1 - time set, 8 - 15 - quality flags of measurement (bits of `Flags`).
+//
+// Holding registers (functions 03, 06, 16) hold settings, see `holding`.
+#[derive(Clone)]
+pub struct ModbusRegisters<'a> {
+    settings: Settings,
+    history: &'a History,
+    recoveries: u32,
+    clock_set: bool,
+    alarms: u16,
+}
+
+impl<'a> ModbusRegisters<'a> {
+    pub fn new(
+        settings: Settings,
+        history: &'a History,
+        recoveries: u32,
+        clock_set: bool,
+        alarms: u16,
+    ) -> ModbusRegisters<'a> {
+        ModbusRegisters {
+            settings,
+            history,
+            recoveries,
+            clock_set,
+            alarms,
+        }
+    }
+
+    // Settings changed by write requests.
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+}
+
+impl<'a> Registers for ModbusRegisters<'a> {
+    fn input(&self, address: u16) -> Option<u16> {
+        let sample = self.history.last();
+        let measurement = sample.map(|sample| sample.measurement());
+        let time = sample.map_or(0, |sample| sample.time());
+        let sequence = self.history.sequence();
+
+        match address {
+            0 => Some(measurement.map_or(0, |measurement| measurement.temperature as i16 as u16)),
+            1 => Some(measurement.map_or(0, |measurement| ((measurement.pressure + 1_280) / 2_560) as u16)),
+            2 => Some(measurement.map_or(0, |measurement| ((100 * measurement.humidity + 512) / 1_024) as u16)),
+            3 => {
+                let mut status = 0;
+
+                if sample.is_some() {
+                    status |= STATUS_MEASUREMENT;
+                }
+
+                if self.clock_set {
+                    status |= STATUS_CLOCK_SET;
+                }
+
+                if let Some(measurement) = measurement {
+                    status |= (measurement.flags.0 as u16) << 8;
+                }
+
+                Some(status)
+            }
+            4 => Some((sequence >> 16) as u16),
+            5 => Some(sequence as u16),
+            6 => Some((time >> 16) as u16),
+            7 => Some(time as u16),
+            8 => Some(self.recoveries.min(u16::MAX as u32) as u16),
+            9 => Some(self.alarms),
+            _ => None,
+        }
+    }
+
+    // Interval in seconds (up to 65535), temperature unit, pressure unit, temperature, pressure and humidity
+    // oversampling, filter coefficient and slave address. Values use numbering of `Settings::get`, writing zero
+    // slave address switches USART back to text commands.
+    fn holding(&self, address: u16) -> Option<u16> {
+        let sampling = self.settings.get(Key::Sampling).to_le_bytes();
+
+        match address {
+            0 => Some(self.settings.interval.min(u16::MAX as u32) as u16),
+            1 => Some(self.settings.get(Key::TemperatureUnit) as u16),
+            2 => Some(self.settings.get(Key::PressureUnit) as u16),
+            3..=6 => Some(sampling[address as usize - 3] as u16),
+            7 => Some(self.settings.modbus_address as u16),
+            _ => None,
+        }
+    }
+
+    fn set_holding(&mut self, address: u16, value: u16) -> Result<(), Exception> {
+        let accepted = match address {
+            0 => self.settings.set(Key::Interval, value as u32),
+            1 => self.settings.set(Key::TemperatureUnit, value as u32),
+            2 => self.settings.set(Key::PressureUnit, value as u32),
+            3..=6 if value <= u8::MAX as u16 => {
+                let mut sampling = self.settings.get(Key::Sampling).to_le_bytes();
+                sampling[address as usize - 3] = value as u8;
+
+                self.settings.set(Key::Sampling, u32::from_le_bytes(sampling))
+            }
+            7 => self.settings.set(Key::ModbusAddress, value as u32),
+            _ => false,
+        };
+
+        if accepted {
+            Ok(())
+        } else {
+            Err(Exception::IllegalDataValue)
+        }
+    }
+}
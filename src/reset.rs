@@ -0,0 +1,60 @@
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use stm32g0xx_hal::stm32::RCC;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetCause {
+    Watchdog,
+    WindowWatchdog,
+    LowPower,
+    Software,
+    OptionBytes,
+    BrownOut,
+    Pin,
+    Unknown,
+}
+
+impl ResetCause {
+    // Reads reset flags from RCC_CSR and clears them, so next reset will report only its own cause. Pin flag
+    // is set for every internal reset too, so it is checked last.
+    pub fn read(rcc: &RCC) -> ResetCause {
+        let flags = rcc.csr.read();
+        let cause = if flags.iwdgrstf().bit_is_set() {
+            ResetCause::Watchdog
+        } else if flags.wwdgrstf().bit_is_set() {
+            ResetCause::WindowWatchdog
+        } else if flags.lpwrrstf().bit_is_set() {
+            ResetCause::LowPower
+        } else if flags.sftrstf().bit_is_set() {
+            ResetCause::Software
+        } else if flags.oblrstf().bit_is_set() {
+            ResetCause::OptionBytes
+        } else if flags.pwrrstf().bit_is_set() {
+            ResetCause::BrownOut
+        } else if flags.pinrstf().bit_is_set() {
+            ResetCause::Pin
+        } else {
+            ResetCause::Unknown
+        };
+
+        rcc.csr.modify(|_, w| w.rmvf().set_bit());
+
+        cause
+    }
+}
+
+impl Display for ResetCause {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            ResetCause::Watchdog => write!(f, "watchdog"),
+            ResetCause::WindowWatchdog => write!(f, "window watchdog"),
+            ResetCause::LowPower => write!(f, "low power"),
+            ResetCause::Software => write!(f, "software"),
+            ResetCause::OptionBytes => write!(f, "option bytes"),
+            ResetCause::BrownOut => write!(f, "brown-out"),
+            ResetCause::Pin => write!(f, "pin"),
+            ResetCause::Unknown => write!(f, "unknown"),
+        }
+    }
+}
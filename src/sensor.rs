@@ -0,0 +1,403 @@
+#[cfg(feature = "smoothing")]
+use bme280::median;
+use bme280::Bme280;
+use bme280::Filter;
+use bme280::HumiditySampling;
+use bme280::PressureSampling;
+use bme280::RateLimit;
+use bme280::SensorMode;
+#[cfg(feature = "smoothing")]
+use bme280::Smoothing;
+use bme280::StandbyTime;
+use bme280::TemperatireSampling;
+use core::ops::RangeInclusive;
+use cortex_m::asm::delay;
+use stm32g0xx_hal::gpio::gpioa::PA11;
+use stm32g0xx_hal::gpio::gpioa::PA12;
+use stm32g0xx_hal::gpio::OpenDrain;
+use stm32g0xx_hal::gpio::Output;
+use stm32g0xx_hal::i2c::Config as I2CConfig;
+use stm32g0xx_hal::i2c::Error as I2CError;
+use stm32g0xx_hal::i2c::I2c;
+use stm32g0xx_hal::i2c::I2cExt;
+use stm32g0xx_hal::prelude::OutputPin;
+use stm32g0xx_hal::prelude::PinState;
+use stm32g0xx_hal::rcc::Rcc;
+use stm32g0xx_hal::stm32::I2C2;
+use stm32g0xx_hal::time::U32Ext;
+use telemetry::command::MAX_BURST;
+use telemetry::Flags;
+
+type I2C = I2c<I2C2, PA12<Output<OpenDrain>>, PA11<Output<OpenDrain>>>;
+
+const I2C_FREQUENCY_KHZ: u32 = 400;
+const RECOVERY_CLOCKS: usize = 9;
+const RECOVERY_HALF_PERIOD: u32 = 80; // CPU cycles, 5 us at 16 MHz.
+const STANDBY_TIME: StandbyTime = StandbyTime::Standby1000;
+const RESET_DELAY: u32 = 32_000; // CPU cycles, 2 ms at 16 MHz.
+const POLL_DELAY: u32 = 16_000; // CPU cycles, 1 ms at 16 MHz.
+const MEASUREMENT_TIMEOUT: u32 = 200; // Polls, longest measurement with x16 oversampling takes 113 ms.
+
+// Physical limits of change: 5 C, 10 hPa and 20% with 0.1 C, 1 hPa and 1% per second.
+const TEMPERATURE_LIMIT: RateLimit = RateLimit { step: 500, rate: 10 };
+const PRESSURE_LIMIT: RateLimit = RateLimit {
+    step: 256_000,
+    rate: 25_600,
+};
+const HUMIDITY_LIMIT: RateLimit = RateLimit {
+    step: 20_480,
+    rate: 1_024,
+};
+
+// Operating range of BME280: -40 - 85 C, 300 - 1100 hPa (Q24.8) and 0 - 100%.
+const OPERATING_RANGE: [RangeInclusive<i32>; 3] = [-4_000..=8_500, 7_680_000..=28_160_000, 0..=102_400];
+const HUMIDITY: usize = 2; // Channel of humidity.
+const MAX_HUMIDITY: i32 = 102_400; // 100% in Q22.10, compensation clamps humidity to 0 - 100%.
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sampling {
+    pub humidity: HumiditySampling,
+    pub temperature: TemperatireSampling,
+    pub pressure: PressureSampling,
+    pub filter: Filter,
+    pub burst: u8,  // Forced measurements for median, 1 disables median.
+    pub weight: u8, // Weight of new value in moving average in %, 100 disables averaging.
+}
+
+#[derive(Debug)]
+pub enum SensorError {
+    Bus(I2CError),
+    Timeout,
+    Rejected, // No valid value yet, bus is not recovered.
+}
+
+impl From<I2CError> for SensorError {
+    fn from(error: I2CError) -> SensorError {
+        SensorError::Bus(error)
+    }
+}
+
+// Chip ID register and I2C address of connected sensor.
+#[derive(Debug, Clone, Copy)]
+pub struct Chip {
+    pub id: u8,
+    pub address: u8,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Measurement {
+    pub temperature: i32,
+    pub pressure: u32,
+    pub humidity: u32,
+    pub flags: Flags, // Quality flags, `Flags::CLOCK_SET` is never set.
+}
+
+// Without smoothing single forced measurement is made and last value is kept, same interface as `median` and
+// `Smoothing` of BME280 driver.
+#[cfg(not(feature = "smoothing"))]
+fn median(values: &mut [i32]) -> Option<i32> {
+    values.first().copied()
+}
+
+#[cfg(not(feature = "smoothing"))]
+#[derive(Debug, Clone, Copy)]
+struct Smoothing(Option<i32>);
+
+#[cfg(not(feature = "smoothing"))]
+impl Smoothing {
+    fn new(_limit: RateLimit, _weight: u8) -> Smoothing {
+        Smoothing(None)
+    }
+
+    fn set_weight(&mut self, _weight: u8) {}
+
+    fn push(&mut self, _time: u32, value: i32) -> bool {
+        self.0 = Some(value);
+
+        true
+    }
+
+    fn value(&self) -> Option<i32> {
+        self.0
+    }
+}
+
+pub struct Sensor {
+    i2c: Option<I2C>,
+    bme280: Option<Bme280>,
+    sampling: Sampling,
+    recoveries: u32,
+    smoothing: [Smoothing; 3],
+    rejected: u32,
+    recovered: bool, // Bus was recovered after last successful measurement.
+    stale: bool,     // Forced measurement of this burst did not start new conversion.
+    chip: Option<Chip>,
+}
+
+impl Sensor {
+    pub fn new(
+        device: I2C2,
+        sda: PA12<Output<OpenDrain>>,
+        scl: PA11<Output<OpenDrain>>,
+        sampling: Sampling,
+        rcc: &mut Rcc,
+    ) -> Sensor {
+        Sensor {
+            i2c: Some(Sensor::create_bus(device, sda, scl, rcc)),
+            bme280: None,
+            sampling,
+            recoveries: 0,
+            smoothing: [
+                Smoothing::new(TEMPERATURE_LIMIT, sampling.weight),
+                Smoothing::new(PRESSURE_LIMIT, sampling.weight),
+                Smoothing::new(HUMIDITY_LIMIT, sampling.weight),
+            ],
+            rejected: 0,
+            recovered: false,
+            stale: false,
+            chip: None,
+        }
+    }
+
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+
+    // Chip found by last initialization, kept after bus error.
+    pub fn chip(&self) -> Option<Chip> {
+        self.chip
+    }
+
+    // Initializes sensor if it is not initialized yet, bus is recovered on error.
+    pub fn probe(&mut self, rcc: &mut Rcc) -> Result<Chip, SensorError> {
+        match self.connect() {
+            Ok(chip) => Ok(chip),
+            Err(error) => {
+                self.recover(rcc);
+
+                Err(error)
+            }
+        }
+    }
+
+    // Readings rejected as skipped by sensor or as spikes.
+    pub fn rejected(&self) -> u32 {
+        self.rejected
+    }
+
+    // New sampling will be applied on next measurement, sensor will be reset to apply configuration in sleep mode.
+    pub fn set_sampling(&mut self, sampling: Sampling) {
+        self.sampling = sampling;
+        self.bme280 = None;
+
+        for smoothing in self.smoothing.iter_mut() {
+            smoothing.set_weight(sampling.weight);
+        }
+    }
+
+    // Median of burst of forced measurements is checked for spikes and averaged, time in seconds. Channel with
+    // zero oversampling reads as zero. Channel without new value keeps previous one and is flagged as skipped, or as
+    // rejected if new value is spike.
+    pub fn measure(&mut self, rcc: &mut Rcc, time: u32) -> Result<Measurement, SensorError> {
+        let burst = if cfg!(feature = "smoothing") {
+            self.sampling.burst.clamp(1, MAX_BURST) as usize
+        } else {
+            1
+        };
+        let mut readings = [[0; MAX_BURST as usize]; 3];
+        let mut counts = [0; 3];
+        let mut flags = Flags::default();
+        self.stale = false;
+
+        for _ in 0..burst {
+            let reading = match self.try_measure() {
+                Ok(reading) => reading,
+                Err(error) => {
+                    self.recover(rcc);
+
+                    return Err(error);
+                }
+            };
+
+            for (channel, value) in reading.iter().enumerate() {
+                match value {
+                    Some(value) => {
+                        readings[channel][counts[channel]] = *value;
+                        counts[channel] += 1;
+                    }
+                    None if self.is_enabled(channel) => self.rejected += 1,
+                    None => {}
+                }
+            }
+        }
+
+        let mut values = [0; 3];
+
+        for (channel, value) in values.iter_mut().enumerate() {
+            if !self.is_enabled(channel) {
+                flags.insert(Flags::SKIPPED);
+                continue;
+            }
+
+            match median(&mut readings[channel][..counts[channel]]) {
+                Some(median) => {
+                    if !self.smoothing[channel].push(time, median) {
+                        self.rejected += 1;
+                        flags.insert(Flags::REJECTED);
+                    } else if channel == HUMIDITY && (median == 0 || median == MAX_HUMIDITY) {
+                        flags.insert(Flags::CLAMPED);
+                    }
+                }
+                None => flags.insert(Flags::SKIPPED),
+            }
+
+            *value = self.smoothing[channel].value().ok_or(SensorError::Rejected)?;
+
+            if !OPERATING_RANGE[channel].contains(value) {
+                flags.insert(Flags::OUT_OF_RANGE);
+            }
+        }
+
+        if self.recovered {
+            flags.insert(Flags::RECOVERED);
+            self.recovered = false;
+        }
+
+        if self.stale {
+            flags.insert(Flags::STALE);
+        }
+
+        Ok(Measurement {
+            temperature: values[0],
+            pressure: values[1] as u32,
+            humidity: values[2] as u32,
+            flags,
+        })
+    }
+
+    fn is_enabled(&self, channel: usize) -> bool {
+        let factor = match channel {
+            0 => self.sampling.temperature.factor(),
+            1 => self.sampling.pressure.factor(),
+            _ => self.sampling.humidity.factor(),
+        };
+
+        factor != 0
+    }
+
+    fn connect(&mut self) -> Result<Chip, SensorError> {
+        let i2c = self.i2c.as_mut().expect("I2C bus is not available");
+
+        if self.bme280.is_none() {
+            let bme280 = Sensor::initialize(i2c, &self.sampling)?;
+
+            self.chip = Some(Chip {
+                id: bme280.id(i2c)?,
+                address: bme280.address(),
+            });
+            self.bme280 = Some(bme280);
+        }
+
+        Ok(self.chip.expect("BME280 is not initialized"))
+    }
+
+    // Temperature, pressure and humidity, `None` if value was skipped.
+    fn try_measure(&mut self) -> Result<[Option<i32>; 3], SensorError> {
+        self.connect()?;
+
+        let i2c = self.i2c.as_mut().expect("I2C bus is not available");
+        let bme280 = self.bme280.as_mut().expect("BME280 is not initialized");
+
+        // In forced mode BME280 makes single measurement and returns to sleep mode.
+        bme280.sampling(
+            i2c,
+            self.sampling.humidity,
+            self.sampling.temperature,
+            self.sampling.pressure,
+            SensorMode::Forced,
+        )?;
+        self.stale |= !Sensor::wait_measurement(bme280, i2c)?;
+
+        let temperature = bme280.temperature(i2c)?;
+        let pressure = bme280.pressure(i2c)?;
+        let humidity = bme280.humidity(i2c)?;
+
+        Ok([
+            temperature,
+            pressure.map(|pressure| pressure as i32),
+            humidity.map(|humidity| humidity as i32),
+        ])
+    }
+
+    // Returns `false` if conversion was not running right after it was triggered, data registers keep previous values
+    // then. Shortest conversion takes about 1 ms, status is read within 0.1 ms at 400 kHz.
+    fn wait_measurement(bme280: &Bme280, i2c: &mut I2C) -> Result<bool, SensorError> {
+        let started = bme280.status(i2c)?.measuring();
+
+        for _ in 0..MEASUREMENT_TIMEOUT {
+            delay(POLL_DELAY);
+
+            if !bme280.status(i2c)?.measuring() {
+                return Ok(started);
+            }
+        }
+
+        Err(SensorError::Timeout)
+    }
+
+    fn create_bus(
+        device: I2C2,
+        sda: PA12<Output<OpenDrain>>,
+        scl: PA11<Output<OpenDrain>>,
+        rcc: &mut Rcc,
+    ) -> I2C {
+        device.i2c(sda, scl, I2CConfig::new(I2C_FREQUENCY_KHZ.khz()), rcc)
+    }
+
+    fn initialize(i2c: &mut I2C, sampling: &Sampling) -> Result<Bme280, I2CError> {
+        let bme280 = Bme280::address_low(i2c)?;
+        bme280.reset(i2c)?;
+
+        delay(RESET_DELAY); // Wait for BME280 to copy calibration data.
+
+        bme280.config(i2c, STANDBY_TIME, sampling.filter)?;
+        bme280.sampling(
+            i2c,
+            sampling.humidity,
+            sampling.temperature,
+            sampling.pressure,
+            SensorMode::Sleep,
+        )?;
+
+        Ok(bme280)
+    }
+
+    // Releases I2C peripheral, clocks SCL until slave releases SDA, generates STOP condition and
+    // creates I2C peripheral again. Sensor will be initialized again on next measurement.
+    fn recover(&mut self, rcc: &mut Rcc) {
+        let i2c = self.i2c.take().expect("I2C bus is not available");
+        let (device, sda, scl) = i2c.release();
+        let mut sda = sda.into_open_drain_output_in_state(PinState::High);
+        let mut scl = scl.into_open_drain_output_in_state(PinState::High);
+
+        for _ in 0..RECOVERY_CLOCKS {
+            let _ = scl.set_low();
+            delay(RECOVERY_HALF_PERIOD);
+            let _ = scl.set_high();
+            delay(RECOVERY_HALF_PERIOD);
+        }
+
+        let _ = scl.set_low();
+        let _ = sda.set_low();
+        delay(RECOVERY_HALF_PERIOD);
+        let _ = scl.set_high();
+        delay(RECOVERY_HALF_PERIOD);
+        let _ = sda.set_high();
+        delay(RECOVERY_HALF_PERIOD);
+
+        self.i2c = Some(Sensor::create_bus(device, sda, scl, rcc));
+        self.bme280 = None;
+        self.recoveries += 1;
+        self.recovered = true;
+    }
+}
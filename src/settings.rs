@@ -0,0 +1,405 @@
+use crate::alarm::AlarmThresholds;
+use crate::bluetooth::BluetoothSettings;
+use crate::sensor::Sampling;
+use bme280::Filter;
+use bme280::HumiditySampling;
+use bme280::PressureSampling;
+use bme280::TemperatireSampling;
+use telemetry::alarm::AlarmKind;
+use telemetry::bluetooth::BluetoothModule;
+use telemetry::bluetooth::Name;
+use telemetry::bluetooth::Pin;
+use telemetry::command::Command;
+use telemetry::command::OutputFormat;
+use telemetry::command::PowerMode;
+use telemetry::command::PressureUnit;
+use telemetry::command::TemperatureUnit;
+use telemetry::command::BAUD_RATES;
+use telemetry::command::MAX_ALTITUDE;
+use telemetry::command::MAX_BURST;
+use telemetry::command::MAX_INTERVAL;
+use telemetry::command::MAX_WINDOW;
+use telemetry::command::MIN_ALTITUDE;
+use telemetry::command::MIN_INTERVAL;
+use telemetry::modbus::MAX_SLAVE_ADDRESS;
+
+const ALARM_DISABLED: u32 = i32::MIN as u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Key {
+    Interval = 1,
+    TemperatureUnit = 2,
+    PressureUnit = 3,
+    Sampling = 4,
+    BaudRate = 5,
+    PowerMode = 6,
+    OutputFormat = 7,
+    ModbusAddress = 8,
+    BluetoothModule = 9,
+    BluetoothName0 = 10,
+    BluetoothName1 = 11,
+    BluetoothName2 = 12,
+    BluetoothPin = 13,
+    BluetoothBaudRate = 14,
+    BluetoothPending = 15,
+    AlarmTemperatureHigh = 16,
+    AlarmTemperatureLow = 17,
+    AlarmHumidityHigh = 18,
+    AlarmHumidityLow = 19,
+    AlarmPressureDrop = 20,
+    Altitude = 21,
+    Smoothing = 22,
+    StatisticsWindows = 23,
+}
+
+pub const KEYS: [Key; 23] = [
+    Key::Interval,
+    Key::TemperatureUnit,
+    Key::PressureUnit,
+    Key::Sampling,
+    Key::BaudRate,
+    Key::PowerMode,
+    Key::OutputFormat,
+    Key::ModbusAddress,
+    Key::BluetoothModule,
+    Key::BluetoothName0,
+    Key::BluetoothName1,
+    Key::BluetoothName2,
+    Key::BluetoothPin,
+    Key::BluetoothBaudRate,
+    Key::BluetoothPending,
+    Key::AlarmTemperatureHigh,
+    Key::AlarmTemperatureLow,
+    Key::AlarmHumidityHigh,
+    Key::AlarmHumidityLow,
+    Key::AlarmPressureDrop,
+    Key::Altitude,
+    Key::Smoothing,
+    Key::StatisticsWindows,
+];
+
+impl Key {
+    pub fn from_u8(value: u8) -> Option<Key> {
+        KEYS.iter().copied().find(|key| *key as u8 == value)
+    }
+
+    // Keys of subsystems disabled by cargo features.
+    #[allow(clippy::match_like_matches_macro)] // Results depend on features.
+    fn is_enabled(self) -> bool {
+        match self {
+            Key::ModbusAddress => cfg!(feature = "modbus"),
+            Key::BluetoothModule
+            | Key::BluetoothName0
+            | Key::BluetoothName1
+            | Key::BluetoothName2
+            | Key::BluetoothPin
+            | Key::BluetoothBaudRate
+            | Key::BluetoothPending => cfg!(feature = "bluetooth"),
+            Key::AlarmTemperatureHigh
+            | Key::AlarmTemperatureLow
+            | Key::AlarmHumidityHigh
+            | Key::AlarmHumidityLow
+            | Key::AlarmPressureDrop => cfg!(feature = "alarms"),
+            Key::Altitude => cfg!(feature = "forecast"),
+            Key::Smoothing => cfg!(feature = "smoothing"),
+            Key::StatisticsWindows => cfg!(feature = "statistics"),
+            _ => true,
+        }
+    }
+
+    fn alarm_kind(self) -> Option<AlarmKind> {
+        match self {
+            Key::AlarmTemperatureHigh => Some(AlarmKind::TemperatureHigh),
+            Key::AlarmTemperatureLow => Some(AlarmKind::TemperatureLow),
+            Key::AlarmHumidityHigh => Some(AlarmKind::HumidityHigh),
+            Key::AlarmHumidityLow => Some(AlarmKind::HumidityLow),
+            Key::AlarmPressureDrop => Some(AlarmKind::PressureDrop),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub interval: u32, // Seconds between measurements.
+    pub temperature_unit: TemperatureUnit,
+    pub pressure_unit: PressureUnit,
+    pub sampling: Sampling,
+    pub baud_rate: u32, // Baud rate of USART and Bluetooth module.
+    pub power_mode: PowerMode,
+    pub output_format: OutputFormat,
+    pub modbus_address: u8, // Zero if USART accepts text commands instead of Modbus RTU requests.
+    pub bluetooth: BluetoothSettings,
+    pub alarms: AlarmThresholds,
+    pub altitude: i32, // Metres above sea level, used to reduce pressure for forecast.
+    pub statistics_windows: (u8, u8), // Hours of short and long statistics window.
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            interval: 60,
+            temperature_unit: TemperatureUnit::Celsius,
+            pressure_unit: PressureUnit::MillimetreMercury,
+            sampling: Sampling {
+                humidity: HumiditySampling::Sampling16,
+                temperature: TemperatireSampling::Sampling16,
+                pressure: PressureSampling::Sampling16,
+                filter: Filter::Off,
+                burst: 1,
+                weight: 100,
+            },
+            baud_rate: 9_600,
+            power_mode: PowerMode::Sleep,
+            output_format: OutputFormat::Text,
+            modbus_address: 0,
+            bluetooth: BluetoothSettings {
+                module: BluetoothModule::None,
+                name: Name::empty(),
+                pin: Pin::empty(),
+                baud_rate: 9_600,
+                pending: false,
+            },
+            alarms: AlarmThresholds::disabled(),
+            altitude: 0,
+            statistics_windows: (1, 24),
+        }
+    }
+}
+
+impl Settings {
+    pub fn get(&self, key: Key) -> u32 {
+        match key {
+            Key::Interval => self.interval,
+            Key::TemperatureUnit => match self.temperature_unit {
+                TemperatureUnit::Celsius => 0,
+                TemperatureUnit::Fahrenheit => 1,
+            },
+            Key::PressureUnit => match self.pressure_unit {
+                PressureUnit::Pascal => 0,
+                PressureUnit::Hectopascal => 1,
+                PressureUnit::MillimetreMercury => 2,
+            },
+            Key::Sampling => u32::from_le_bytes([
+                self.sampling.temperature.factor(),
+                self.sampling.pressure.factor(),
+                self.sampling.humidity.factor(),
+                self.sampling.filter.coefficient(),
+            ]),
+            Key::BaudRate => self.baud_rate,
+            Key::PowerMode => match self.power_mode {
+                PowerMode::Sleep => 0,
+                PowerMode::Stop => 1,
+            },
+            Key::OutputFormat => match self.output_format {
+                OutputFormat::Text => 0,
+                OutputFormat::Csv => 1,
+                OutputFormat::Json => 2,
+                OutputFormat::Influx => 3,
+                OutputFormat::Binary => 4,
+                OutputFormat::Nmea => 5,
+            },
+            Key::ModbusAddress => self.modbus_address as u32,
+            Key::BluetoothModule => match self.bluetooth.module {
+                BluetoothModule::None => 0,
+                BluetoothModule::Hc05 => 1,
+                BluetoothModule::Hc06 => 2,
+                BluetoothModule::Hm10 => 3,
+            },
+            Key::BluetoothName0 => self.bluetooth.name.chunk(0),
+            Key::BluetoothName1 => self.bluetooth.name.chunk(1),
+            Key::BluetoothName2 => self.bluetooth.name.chunk(2),
+            Key::BluetoothPin => self.bluetooth.pin.to_u32(),
+            Key::BluetoothBaudRate => self.bluetooth.baud_rate,
+            Key::BluetoothPending => self.bluetooth.pending as u32,
+            Key::AlarmTemperatureHigh
+            | Key::AlarmTemperatureLow
+            | Key::AlarmHumidityHigh
+            | Key::AlarmHumidityLow
+            | Key::AlarmPressureDrop => key
+                .alarm_kind()
+                .and_then(|kind| self.alarms.get(kind))
+                .map_or(ALARM_DISABLED, |threshold| threshold as u32),
+            Key::Altitude => self.altitude as u32,
+            Key::Smoothing => u32::from_le_bytes([self.sampling.burst, self.sampling.weight, 0, 0]),
+            Key::StatisticsWindows => {
+                u32::from_le_bytes([self.statistics_windows.0, self.statistics_windows.1, 0, 0])
+            }
+        }
+    }
+
+    // Sets value previously returned by `get`, invalid values ignored. Returns `true` if value was accepted.
+    pub fn set(&mut self, key: Key, value: u32) -> bool {
+        // Firmware without subsystem keeps defaults, stored slave address would lock out text commands.
+        if !key.is_enabled() {
+            return false;
+        }
+
+        match key {
+            Key::Interval if (MIN_INTERVAL..=MAX_INTERVAL).contains(&value) => self.interval = value,
+            Key::TemperatureUnit if value == 0 => self.temperature_unit = TemperatureUnit::Celsius,
+            Key::TemperatureUnit if value == 1 => self.temperature_unit = TemperatureUnit::Fahrenheit,
+            Key::PressureUnit if value == 0 => self.pressure_unit = PressureUnit::Pascal,
+            Key::PressureUnit if value == 1 => self.pressure_unit = PressureUnit::Hectopascal,
+            Key::PressureUnit if value == 2 => self.pressure_unit = PressureUnit::MillimetreMercury,
+            Key::Sampling => {
+                let [temperature, pressure, humidity, filter] = value.to_le_bytes();
+
+                match (
+                    TemperatireSampling::from_factor(temperature),
+                    PressureSampling::from_factor(pressure),
+                    HumiditySampling::from_factor(humidity),
+                    Filter::from_coefficient(filter),
+                ) {
+                    (Some(temperature), Some(pressure), Some(humidity), Some(filter)) => {
+                        self.sampling.temperature = temperature;
+                        self.sampling.pressure = pressure;
+                        self.sampling.humidity = humidity;
+                        self.sampling.filter = filter;
+                    }
+                    _ => return false,
+                }
+            }
+            Key::BaudRate if BAUD_RATES.contains(&value) => self.baud_rate = value,
+            Key::PowerMode if value == 0 => self.power_mode = PowerMode::Sleep,
+            Key::PowerMode if value == 1 => self.power_mode = PowerMode::Stop,
+            Key::OutputFormat if value == 0 => self.output_format = OutputFormat::Text,
+            Key::OutputFormat if value == 1 => self.output_format = OutputFormat::Csv,
+            Key::OutputFormat if value == 2 => self.output_format = OutputFormat::Json,
+            Key::OutputFormat if value == 3 => self.output_format = OutputFormat::Influx,
+            Key::OutputFormat if value == 4 && cfg!(feature = "binary") => self.output_format = OutputFormat::Binary,
+            Key::OutputFormat if value == 5 && cfg!(feature = "nmea") => self.output_format = OutputFormat::Nmea,
+            Key::ModbusAddress if value <= MAX_SLAVE_ADDRESS as u32 => self.modbus_address = value as u8,
+            Key::BluetoothModule if value == 0 => self.bluetooth.module = BluetoothModule::None,
+            Key::BluetoothModule if value == 1 => self.bluetooth.module = BluetoothModule::Hc05,
+            Key::BluetoothModule if value == 2 => self.bluetooth.module = BluetoothModule::Hc06,
+            Key::BluetoothModule if value == 3 => self.bluetooth.module = BluetoothModule::Hm10,
+            Key::BluetoothName0 => return self.bluetooth.name.set_chunk(0, value),
+            Key::BluetoothName1 => return self.bluetooth.name.set_chunk(1, value),
+            Key::BluetoothName2 => return self.bluetooth.name.set_chunk(2, value),
+            Key::BluetoothPin => match Pin::from_u32(value) {
+                Some(pin) => self.bluetooth.pin = pin,
+                None => return false,
+            },
+            Key::BluetoothBaudRate if BAUD_RATES.contains(&value) => self.bluetooth.baud_rate = value,
+            Key::BluetoothPending if value <= 1 => self.bluetooth.pending = value == 1,
+            Key::AlarmTemperatureHigh
+            | Key::AlarmTemperatureLow
+            | Key::AlarmHumidityHigh
+            | Key::AlarmHumidityLow
+            | Key::AlarmPressureDrop => {
+                let threshold = if value == ALARM_DISABLED { None } else { Some(value as i32) };
+
+                return key
+                    .alarm_kind()
+                    .is_some_and(|kind| self.alarms.set(kind, threshold));
+            }
+            Key::Altitude if (MIN_ALTITUDE..=MAX_ALTITUDE).contains(&(value as i32)) => self.altitude = value as i32,
+            Key::Smoothing => {
+                let [burst, weight, _, _] = value.to_le_bytes();
+
+                if !(1..=MAX_BURST).contains(&burst) || !(1..=100).contains(&weight) {
+                    return false;
+                }
+
+                self.sampling.burst = burst;
+                self.sampling.weight = weight;
+            }
+            Key::StatisticsWindows => {
+                let [short, long, _, _] = value.to_le_bytes();
+
+                if !(1..=MAX_WINDOW).contains(&short) || !(1..=MAX_WINDOW).contains(&long) {
+                    return false;
+                }
+
+                self.statistics_windows = (short, long);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    // Returns `false` if command does not fit other settings, settings are not changed then.
+    pub fn update(&mut self, command: Command) -> bool {
+        match command {
+            Command::Interval(interval) => self.interval = interval,
+            Command::Units(temperature_unit, pressure_unit) => {
+                if let Some(temperature_unit) = temperature_unit {
+                    self.temperature_unit = temperature_unit;
+                }
+
+                if let Some(pressure_unit) = pressure_unit {
+                    self.pressure_unit = pressure_unit;
+                }
+            }
+            Command::Oversample(temperature, pressure, humidity) => {
+                if let Some(temperature) = temperature {
+                    self.sampling.temperature = temperature;
+                }
+
+                if let Some(pressure) = pressure {
+                    self.sampling.pressure = pressure;
+                }
+
+                if let Some(humidity) = humidity {
+                    self.sampling.humidity = humidity;
+                }
+            }
+            Command::Filter(filter) => self.sampling.filter = filter,
+            Command::Power(power_mode) => self.power_mode = power_mode,
+            Command::Format(output_format) => self.output_format = output_format,
+            Command::Modbus(address) => self.modbus_address = address,
+            Command::Altitude(altitude) => self.altitude = altitude,
+            Command::Smooth(burst, weight) => {
+                self.sampling.burst = burst;
+                self.sampling.weight = weight;
+            }
+            // Without module to provision new baud rate is used after reset, module must be configured manually.
+            Command::Baud(baud_rate) => {
+                self.bluetooth.baud_rate = baud_rate;
+
+                if self.bluetooth.module == BluetoothModule::None || !cfg!(feature = "bluetooth") {
+                    self.baud_rate = baud_rate;
+                } else {
+                    self.bluetooth.pending = true;
+                }
+            }
+            // PIN for other module type is not sent to new one.
+            Command::Bluetooth(module) => {
+                if !module.accepts(self.bluetooth.pin) {
+                    self.bluetooth.pin = Pin::empty();
+                }
+
+                self.bluetooth.module = module;
+                self.bluetooth.baud_rate = self.baud_rate;
+                self.bluetooth.pending = module != BluetoothModule::None;
+            }
+            Command::BluetoothName(name) => {
+                self.bluetooth.name = name;
+                self.bluetooth.pending = self.bluetooth.module != BluetoothModule::None;
+            }
+            Command::BluetoothPin(pin) if !self.bluetooth.module.accepts(pin) => return false,
+            Command::BluetoothPin(pin) => {
+                self.bluetooth.pin = pin;
+                self.bluetooth.pending = self.bluetooth.module != BluetoothModule::None;
+            }
+            Command::Alarm(kind, threshold) => {
+                self.alarms.set(kind, threshold);
+            }
+            Command::StatisticsWindows(short, long) => self.statistics_windows = (short, long),
+            Command::Read
+            | Command::Alarms
+            | Command::History
+            | Command::ClearHistory
+            | Command::Statistics
+            | Command::ClearStatistics
+            | Command::Time(..)
+            | Command::Reset
+            | Command::Info => {}
+        }
+
+        true
+    }
+}
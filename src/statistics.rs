@@ -0,0 +1,56 @@
+use crate::sensor::Measurement;
+use bme280::RollingStatistics;
+
+pub const TEMPERATURE: usize = 0;
+pub const PRESSURE: usize = 1;
+pub const HUMIDITY: usize = 2;
+
+const SHORT_BUCKETS: usize = 6;
+const LONG_BUCKETS: usize = 12;
+
+// Statistics of temperature in 0.01 C, pressure in Pa and humidity in % (Q22.10 fixed point). Windows are set in hours,
+// by default 1 hour window is kept in 6 buckets of 10 minutes and 24 hours window in 12 buckets of 2 hours.
+pub struct Statistics {
+    pub short: RollingStatistics<3, SHORT_BUCKETS>,
+    pub long: RollingStatistics<3, LONG_BUCKETS>,
+}
+
+impl Statistics {
+    pub const fn new() -> Statistics {
+        Statistics {
+            short: RollingStatistics::new(bucket_duration(1, SHORT_BUCKETS)),
+            long: RollingStatistics::new(bucket_duration(24, LONG_BUCKETS)),
+        }
+    }
+
+    // Changed window is cleared.
+    pub fn set_windows(&mut self, windows: (u8, u8)) {
+        if self.short.duration() != windows.0 as u32 * 3_600 {
+            self.short.set_bucket_duration(bucket_duration(windows.0, SHORT_BUCKETS));
+        }
+
+        if self.long.duration() != windows.1 as u32 * 3_600 {
+            self.long.set_bucket_duration(bucket_duration(windows.1, LONG_BUCKETS));
+        }
+    }
+
+    pub fn push(&mut self, time: u32, measurement: &Measurement) {
+        let values = [
+            measurement.temperature,
+            (measurement.pressure >> 8) as i32,
+            measurement.humidity as i32,
+        ];
+
+        self.short.push(time, values);
+        self.long.push(time, values);
+    }
+
+    pub fn clear(&mut self) {
+        self.short.clear();
+        self.long.clear();
+    }
+}
+
+const fn bucket_duration(hours: u8, buckets: usize) -> u32 {
+    hours as u32 * 3_600 / buckets as u32
+}
@@ -0,0 +1,237 @@
+use crate::settings::Key;
+use crate::settings::Settings;
+use crate::settings::KEYS;
+use core::ptr::read_volatile;
+use stm32g0xx_hal::flash::Error as FlashError;
+use stm32g0xx_hal::flash::FlashExt;
+use stm32g0xx_hal::flash::FlashPage;
+use stm32g0xx_hal::flash::UnlockedFlash;
+use stm32g0xx_hal::flash::WriteErase;
+use stm32g0xx_hal::stm32::FLASH;
+use telemetry::crc16;
+
+// Settings are kept in two last flash pages (reserved in `memory.x`) as log of 8 byte records, each record holds
+// single value and programmed as one double word. Changed values appended to active page, when page is full
+// current values copied to other page with greater sequence number. Page header is programmed last, so interrupted
+// copying leaves previous page active. Records with invalid CRC ignored, missing values use defaults.
+const PAGES: [usize; 2] = [14, 15];
+const FLASH_ORIGIN: usize = 0x0800_0000;
+const PAGE_SIZE: usize = 2048;
+const RECORD_SIZE: usize = 8;
+const HEADER_MAGIC: u16 = 0x5354;
+const STORAGE_VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Locked,
+    Flash(FlashError),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Page {
+    index: usize,
+    sequence: u16,
+    free: usize, // Address of first free record.
+}
+
+impl Page {
+    fn read(index: usize) -> Option<Page> {
+        let start = page_address(index);
+        let header = read_double_word(start);
+
+        if header[..2] != HEADER_MAGIC.to_le_bytes()
+            || header[2] != STORAGE_VERSION
+            || header[6..] != crc16(&header[..6]).to_le_bytes()
+        {
+            return None;
+        }
+
+        let mut free = start + RECORD_SIZE;
+
+        for address in (start + RECORD_SIZE..start + PAGE_SIZE).step_by(RECORD_SIZE) {
+            if read_double_word(address) != [0xFF; RECORD_SIZE] {
+                free = address + RECORD_SIZE;
+            }
+        }
+
+        Some(Page {
+            index,
+            sequence: u16::from_le_bytes([header[4], header[5]]),
+            free,
+        })
+    }
+
+    fn records(&self) -> impl Iterator<Item = usize> {
+        (page_address(self.index) + RECORD_SIZE..self.free).step_by(RECORD_SIZE)
+    }
+
+    fn available(&self) -> usize {
+        (page_address(self.index) + PAGE_SIZE - self.free) / RECORD_SIZE
+    }
+}
+
+pub struct Storage {
+    flash: Option<FLASH>,
+    active: Option<Page>,
+}
+
+impl Storage {
+    pub fn new(flash: FLASH) -> Storage {
+        let mut active: Option<Page> = None;
+
+        for index in PAGES {
+            if let Some(page) = Page::read(index) {
+                match active {
+                    Some(current) if !is_newer(page.sequence, current.sequence) => {}
+                    _ => active = Some(page),
+                }
+            }
+        }
+
+        Storage {
+            flash: Some(flash),
+            active,
+        }
+    }
+
+    pub fn load(&self) -> Settings {
+        let mut settings = Settings::default();
+
+        if let Some(page) = self.active {
+            for address in page.records() {
+                if let Some((key, value)) = decode_record(read_double_word(address)) {
+                    settings.set(key, value);
+                }
+            }
+        }
+
+        settings
+    }
+
+    pub fn store(&mut self, settings: &Settings) -> Result<(), StorageError> {
+        let stored = self.load();
+        let changed = KEYS
+            .iter()
+            .filter(|key| stored.get(**key) != settings.get(**key))
+            .count();
+
+        match self.active {
+            Some(_) if changed == 0 => Ok(()),
+            Some(page) if changed <= page.available() => self.append(page, &stored, settings),
+            _ => self.compact(settings),
+        }
+    }
+
+    fn append(&mut self, page: Page, stored: &Settings, settings: &Settings) -> Result<(), StorageError> {
+        let mut free = page.free;
+        let result = self.with_flash(|flash| {
+            for key in KEYS {
+                let value = settings.get(key);
+
+                if stored.get(key) != value {
+                    flash.write(free, &encode_record(key, value))?;
+                    free += RECORD_SIZE;
+                }
+            }
+
+            Ok(())
+        });
+        self.active = Some(Page { free, ..page });
+
+        result
+    }
+
+    fn compact(&mut self, settings: &Settings) -> Result<(), StorageError> {
+        let (index, sequence) = match self.active {
+            Some(page) if page.index == PAGES[0] => (PAGES[1], page.sequence.wrapping_add(1)),
+            Some(page) => (PAGES[0], page.sequence.wrapping_add(1)),
+            None => (PAGES[0], 0),
+        };
+        let start = page_address(index);
+        let mut free = start + RECORD_SIZE;
+
+        self.with_flash(|flash| {
+            flash.erase_page(FlashPage(index))?;
+
+            for key in KEYS {
+                flash.write(free, &encode_record(key, settings.get(key)))?;
+                free += RECORD_SIZE;
+            }
+
+            flash.write(start, &encode_header(sequence))
+        })?;
+        self.active = Some(Page {
+            index,
+            sequence,
+            free,
+        });
+
+        Ok(())
+    }
+
+    fn with_flash<F>(&mut self, callback: F) -> Result<(), StorageError>
+    where
+        F: FnOnce(&mut UnlockedFlash) -> Result<(), FlashError>,
+    {
+        let flash = self.flash.take().expect("Flash is not available");
+        let mut unlocked = match flash.unlock() {
+            Ok(unlocked) => unlocked,
+            Err(flash) => {
+                self.flash = Some(flash);
+
+                return Err(StorageError::Locked);
+            }
+        };
+        let result = callback(&mut unlocked);
+        self.flash = Some(unlocked.lock());
+
+        result.map_err(StorageError::Flash)
+    }
+}
+
+fn page_address(index: usize) -> usize {
+    FLASH_ORIGIN + index * PAGE_SIZE
+}
+
+fn is_newer(sequence: u16, other: u16) -> bool {
+    (sequence.wrapping_sub(other) as i16) > 0
+}
+
+fn encode_header(sequence: u16) -> [u8; RECORD_SIZE] {
+    let mut header = [0; RECORD_SIZE];
+    header[..2].copy_from_slice(&HEADER_MAGIC.to_le_bytes());
+    header[2] = STORAGE_VERSION;
+    header[4..6].copy_from_slice(&sequence.to_le_bytes());
+
+    let crc = crc16(&header[..6]);
+    header[6..].copy_from_slice(&crc.to_le_bytes());
+
+    header
+}
+
+fn encode_record(key: Key, value: u32) -> [u8; RECORD_SIZE] {
+    let mut record = [0; RECORD_SIZE];
+    record[0] = key as u8;
+    record[2..6].copy_from_slice(&value.to_le_bytes());
+
+    let crc = crc16(&record[..6]);
+    record[6..].copy_from_slice(&crc.to_le_bytes());
+
+    record
+}
+
+fn decode_record(record: [u8; RECORD_SIZE]) -> Option<(Key, u32)> {
+    if record[6..] != crc16(&record[..6]).to_le_bytes() {
+        return None;
+    }
+
+    let key = Key::from_u8(record[0])?;
+    let value = u32::from_le_bytes([record[2], record[3], record[4], record[5]]);
+
+    Some((key, value))
+}
+
+#[allow(unsafe_code)]
+fn read_double_word(address: usize) -> [u8; RECORD_SIZE] {
+    unsafe { read_volatile(address as *const u64) }.to_le_bytes()
+}
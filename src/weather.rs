@@ -0,0 +1,42 @@
+use crate::history::History;
+use crate::history::Sample;
+use bme280::sea_level_pressure;
+use bme280::Forecast;
+use bme280::PressureTendency;
+
+const TENDENCY_PERIOD: u32 = 10_800; // Seconds, tendency is pressure change during 3 hours.
+const SAMPLE_TOLERANCE: u32 = 900; // Seconds, allowed difference of sample age from 3 and 1.5 hours.
+
+#[derive(Debug, Clone, Copy)]
+pub struct Weather {
+    pub tendency: PressureTendency,
+    pub change: i32, // Pa during last 3 hours.
+    pub forecast: Forecast,
+}
+
+// Tendency and forecast from samples measured 3 and 1.5 hours ago, `None` if history does not cover 3 hours (for
+// example after reset or with interval below 54 seconds). Pressure is in Q24.8 fixed point.
+pub fn weather(history: &History, time: u32, pressure: u32, altitude: i32) -> Option<Weather> {
+    let before = sample_pressure(history, time, TENDENCY_PERIOD)?;
+    let middle = sample_pressure(history, time, TENDENCY_PERIOD / 2)?;
+    let pressure = (pressure >> 8) as i32;
+    let change = pressure - before;
+
+    Some(Weather {
+        tendency: PressureTendency::from_pressures(before, middle, pressure),
+        change,
+        forecast: Forecast::zambretti(sea_level_pressure(pressure as u32, altitude), change),
+    })
+}
+
+// Pressure in Pa of sample which age is the closest to given one.
+fn sample_pressure(history: &History, time: u32, age: u32) -> Option<i32> {
+    let distance = |sample: &Sample| time.wrapping_sub(sample.time()).abs_diff(age);
+
+    history
+        .iter()
+        .map(|(_, sample)| sample)
+        .filter(|sample| distance(sample) <= SAMPLE_TOLERANCE)
+        .min_by_key(|sample| distance(sample))
+        .map(|sample| (sample.measurement().pressure >> 8) as i32)
+}
@@ -0,0 +1,46 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmKind {
+    TemperatureHigh,
+    TemperatureLow,
+    HumidityHigh,
+    HumidityLow,
+    PressureDrop,
+}
+
+pub const ALARM_KINDS: [AlarmKind; 5] = [
+    AlarmKind::TemperatureHigh,
+    AlarmKind::TemperatureLow,
+    AlarmKind::HumidityHigh,
+    AlarmKind::HumidityLow,
+    AlarmKind::PressureDrop,
+];
+
+impl AlarmKind {
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    // Limits of thresholds: temperature in 0.01 C, humidity in 0.01% and pressure drop in Pa per 3 hours.
+    pub fn range(self) -> (i32, i32) {
+        match self {
+            AlarmKind::TemperatureHigh | AlarmKind::TemperatureLow => (-4_000, 8_500),
+            AlarmKind::HumidityHigh | AlarmKind::HumidityLow => (0, 10_000),
+            AlarmKind::PressureDrop => (10, 5_000),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        for (index, kind) in ALARM_KINDS.iter().enumerate() {
+            let (min, max) = kind.range();
+
+            assert_eq!(kind.index(), index);
+            assert!(min < max);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+
+pub const NAME_SIZE: usize = 12; // HM-10 accepts up to 12 characters.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BluetoothModule {
+    None,
+    Hc05,
+    Hc06,
+    Hm10,
+}
+
+impl BluetoothModule {
+    // HC-05 and HC-06 use PIN of 4 digits, HM-10 of 6 digits. Without module and empty PIN length is not checked.
+    pub fn accepts(self, pin: Pin) -> bool {
+        match self {
+            _ if pin.is_empty() => true,
+            BluetoothModule::None => true,
+            BluetoothModule::Hc05 | BluetoothModule::Hc06 => pin.len() == 4,
+            BluetoothModule::Hm10 => pin.len() == 6,
+        }
+    }
+}
+
+// Module name of ASCII letters, digits, `-` and `_`, empty name is not changed during provisioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Name {
+    bytes: [u8; NAME_SIZE],
+    length: usize,
+}
+
+impl Name {
+    pub const fn empty() -> Name {
+        Name {
+            bytes: [0; NAME_SIZE],
+            length: 0,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Name> {
+        if value.len() > NAME_SIZE || !value.bytes().all(is_name_byte) {
+            return None;
+        }
+
+        let mut name = Name::empty();
+        name.bytes[..value.len()].copy_from_slice(value.as_bytes());
+        name.length = value.len();
+
+        Some(name)
+    }
+
+    // Name is stored as three values, each holds four bytes padded with zeros.
+    pub fn chunk(&self, index: usize) -> u32 {
+        let mut chunk = [0; 4];
+        chunk.copy_from_slice(&self.bytes[index * 4..index * 4 + 4]);
+
+        u32::from_le_bytes(chunk)
+    }
+
+    pub fn set_chunk(&mut self, index: usize, value: u32) -> bool {
+        let chunk = value.to_le_bytes();
+
+        if !chunk.iter().all(|byte| *byte == 0 || is_name_byte(*byte)) {
+            return false;
+        }
+
+        self.bytes[index * 4..index * 4 + 4].copy_from_slice(&chunk);
+        self.length = self.bytes.iter().position(|byte| *byte == 0).unwrap_or(NAME_SIZE);
+
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.length]).unwrap_or("")
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+// PIN of 4 (HC-05, HC-06) or 6 (HM-10) digits, stored as number with digits count in the highest byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pin(u32);
+
+impl Pin {
+    pub const fn empty() -> Pin {
+        Pin(0)
+    }
+
+    pub fn parse(value: &str) -> Option<Pin> {
+        if (value.len() != 4 && value.len() != 6) || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        let number: u32 = value.parse().ok()?;
+
+        Some(Pin((value.len() as u32) << 24 | number))
+    }
+
+    pub fn from_u32(value: u32) -> Option<Pin> {
+        let length = value >> 24;
+        let number = value & 0x00FF_FFFF;
+
+        match length {
+            0 if number == 0 => Some(Pin::empty()),
+            4 if number < 10_000 => Some(Pin(value)),
+            6 if number < 1_000_000 => Some(Pin(value)),
+            _ => None,
+        }
+    }
+
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+
+    // Number of digits.
+    pub fn len(&self) -> usize {
+        (self.0 >> 24) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Display for Pin {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:01$}", self.0 & 0x00FF_FFFF, self.len())
+    }
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name() {
+        let name = Name::parse("bme-280_1").unwrap();
+
+        assert_eq!(name.as_str(), "bme-280_1");
+        assert_eq!(name.chunk(0), u32::from_le_bytes(*b"bme-"));
+        assert_eq!(name.chunk(2), u32::from_le_bytes([b'1', 0, 0, 0]));
+
+        let mut copy = Name::empty();
+
+        for index in 0..3 {
+            assert!(copy.set_chunk(index, name.chunk(index)));
+        }
+
+        assert_eq!(copy, name);
+        assert!(!copy.set_chunk(0, u32::from_le_bytes(*b"a b ")));
+        assert_eq!(copy, name);
+
+        assert!(Name::parse("").unwrap().is_empty());
+        assert_eq!(Name::parse("sensor name"), None);
+        assert_eq!(Name::parse("thirteen-char"), None);
+    }
+
+    #[test]
+    fn pin() {
+        let pin = Pin::parse("0042").unwrap();
+
+        assert_eq!(pin.to_u32(), 4 << 24 | 42);
+        assert_eq!(Pin::from_u32(pin.to_u32()), Some(pin));
+        assert_eq!(Pin::parse("123456").map(Pin::to_u32), Some(6 << 24 | 123_456));
+        assert_eq!(Pin::from_u32(0), Some(Pin::empty()));
+
+        assert_eq!(Pin::parse("12345"), None);
+        assert_eq!(Pin::parse("12a4"), None);
+        assert_eq!(Pin::parse("+123"), None);
+        assert_eq!(Pin::from_u32(4 << 24 | 10_000), None);
+        assert_eq!(Pin::from_u32(5 << 24 | 42), None);
+    }
+
+    #[test]
+    fn pin_length() {
+        let short = Pin::parse("1234").unwrap();
+        let long = Pin::parse("012345").unwrap();
+
+        assert_eq!(long.len(), 6);
+        assert!(BluetoothModule::Hc05.accepts(short));
+        assert!(!BluetoothModule::Hc06.accepts(long));
+        assert!(BluetoothModule::Hm10.accepts(long));
+        assert!(!BluetoothModule::Hm10.accepts(short));
+        assert!(BluetoothModule::Hm10.accepts(Pin::empty()));
+        assert!(BluetoothModule::None.accepts(short));
+    }
+}
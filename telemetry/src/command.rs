@@ -0,0 +1,648 @@
+use crate::alarm::AlarmKind;
+use crate::bluetooth::BluetoothModule;
+use crate::bluetooth::Name;
+use crate::bluetooth::Pin;
+use crate::modbus::MAX_SLAVE_ADDRESS;
+use crate::time::DateTime;
+use bme280::Filter;
+use bme280::HumiditySampling;
+use bme280::PressureSampling;
+use bme280::TemperatireSampling;
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::str::from_utf8;
+
+pub const MIN_INTERVAL: u32 = 1;
+pub const MAX_INTERVAL: u32 = 86_400;
+pub const MIN_ALTITUDE: i32 = -500;
+pub const MAX_ALTITUDE: i32 = 9_000;
+pub const BAUD_RATES: [u32; 8] = [1_200, 2_400, 4_800, 9_600, 19_200, 38_400, 57_600, 115_200];
+pub const MAX_BURST: u8 = 7; // Forced measurements of which median is taken.
+pub const MAX_WINDOW: u8 = 168; // Hours of statistics window, one week.
+
+const LINE_SIZE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureUnit {
+    Pascal,
+    Hectopascal,
+    MillimetreMercury,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Csv,
+    Json,
+    Influx,
+    Binary,
+    Nmea,
+}
+
+// Sleep keeps USART receiving commands, in Stop mode bytes received while MCU is stopped are lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerMode {
+    Sleep,
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Read,
+    Interval(u32),
+    Units(Option<TemperatureUnit>, Option<PressureUnit>),
+    Oversample(
+        Option<TemperatireSampling>,
+        Option<PressureSampling>,
+        Option<HumiditySampling>,
+    ),
+    Filter(Filter),
+    Smooth(u8, u8),
+    Power(PowerMode),
+    Format(OutputFormat),
+    Modbus(u8),
+    Altitude(i32),
+    Alarm(AlarmKind, Option<i32>),
+    Alarms,
+    Baud(u32),
+    Bluetooth(BluetoothModule),
+    BluetoothName(Name),
+    BluetoothPin(Pin),
+    History,
+    ClearHistory,
+    Statistics,
+    ClearStatistics,
+    StatisticsWindows(u8, u8),
+    Time(Option<DateTime>),
+    Reset,
+    Info,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandError {
+    LineTooLong,
+    UnknownCommand,
+    MissingArgument,
+    InvalidArgument,
+    TooManyArguments,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            CommandError::LineTooLong => write!(f, "line too long"),
+            CommandError::UnknownCommand => write!(f, "unknown command"),
+            CommandError::MissingArgument => write!(f, "missing argument"),
+            CommandError::InvalidArgument => write!(f, "invalid argument"),
+            CommandError::TooManyArguments => write!(f, "too many arguments"),
+        }
+    }
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Command, CommandError> {
+        let mut tokens = line.split_ascii_whitespace();
+        let name = tokens.next().ok_or(CommandError::UnknownCommand)?;
+        let command = if name.eq_ignore_ascii_case("READ") {
+            Command::Read
+        } else if name.eq_ignore_ascii_case("INTERVAL") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let interval = argument
+                .parse()
+                .map_err(|_| CommandError::InvalidArgument)?;
+
+            if !(MIN_INTERVAL..=MAX_INTERVAL).contains(&interval) {
+                return Err(CommandError::InvalidArgument);
+            }
+
+            Command::Interval(interval)
+        } else if name.eq_ignore_ascii_case("UNITS") {
+            let mut temperature = None;
+            let mut pressure = None;
+
+            for token in tokens.by_ref() {
+                if token.eq_ignore_ascii_case("C") {
+                    temperature = Some(TemperatureUnit::Celsius);
+                } else if token.eq_ignore_ascii_case("F") {
+                    temperature = Some(TemperatureUnit::Fahrenheit);
+                } else if token.eq_ignore_ascii_case("PA") {
+                    pressure = Some(PressureUnit::Pascal);
+                } else if token.eq_ignore_ascii_case("HPA") {
+                    pressure = Some(PressureUnit::Hectopascal);
+                } else if token.eq_ignore_ascii_case("MMHG") {
+                    pressure = Some(PressureUnit::MillimetreMercury);
+                } else {
+                    return Err(CommandError::InvalidArgument);
+                }
+            }
+
+            if temperature.is_none() && pressure.is_none() {
+                return Err(CommandError::MissingArgument);
+            }
+
+            Command::Units(temperature, pressure)
+        } else if name.eq_ignore_ascii_case("OVERSAMPLE") {
+            let mut temperature = None;
+            let mut pressure = None;
+            let mut humidity = None;
+
+            for token in tokens.by_ref() {
+                let channel = token.get(..1).ok_or(CommandError::InvalidArgument)?;
+                let factor = token
+                    .get(1..)
+                    .and_then(|factor| factor.parse().ok())
+                    .ok_or(CommandError::InvalidArgument)?;
+
+                if channel.eq_ignore_ascii_case("T") {
+                    temperature = Some(
+                        TemperatireSampling::from_factor(factor)
+                            .ok_or(CommandError::InvalidArgument)?,
+                    );
+                } else if channel.eq_ignore_ascii_case("P") {
+                    pressure = Some(
+                        PressureSampling::from_factor(factor).ok_or(CommandError::InvalidArgument)?,
+                    );
+                } else if channel.eq_ignore_ascii_case("H") {
+                    humidity = Some(
+                        HumiditySampling::from_factor(factor).ok_or(CommandError::InvalidArgument)?,
+                    );
+                } else {
+                    return Err(CommandError::InvalidArgument);
+                }
+            }
+
+            if temperature.is_none() && pressure.is_none() && humidity.is_none() {
+                return Err(CommandError::MissingArgument);
+            }
+
+            Command::Oversample(temperature, pressure, humidity)
+        } else if name.eq_ignore_ascii_case("FILTER") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let coefficient = argument
+                .parse()
+                .map_err(|_| CommandError::InvalidArgument)?;
+            let filter = Filter::from_coefficient(coefficient).ok_or(CommandError::InvalidArgument)?;
+
+            Command::Filter(filter)
+        } else if cfg!(feature = "smoothing") && name.eq_ignore_ascii_case("SMOOTH") {
+            let burst = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let weight = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let burst = burst.parse().map_err(|_| CommandError::InvalidArgument)?;
+            let weight = weight.parse().map_err(|_| CommandError::InvalidArgument)?;
+
+            if !(1..=MAX_BURST).contains(&burst) || !(1..=100).contains(&weight) {
+                return Err(CommandError::InvalidArgument);
+            }
+
+            Command::Smooth(burst, weight)
+        } else if name.eq_ignore_ascii_case("POWER") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+
+            if argument.eq_ignore_ascii_case("SLEEP") {
+                Command::Power(PowerMode::Sleep)
+            } else if argument.eq_ignore_ascii_case("STOP") {
+                Command::Power(PowerMode::Stop)
+            } else {
+                return Err(CommandError::InvalidArgument);
+            }
+        } else if name.eq_ignore_ascii_case("FORMAT") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+
+            if argument.eq_ignore_ascii_case("TEXT") {
+                Command::Format(OutputFormat::Text)
+            } else if argument.eq_ignore_ascii_case("CSV") {
+                Command::Format(OutputFormat::Csv)
+            } else if argument.eq_ignore_ascii_case("JSON") {
+                Command::Format(OutputFormat::Json)
+            } else if argument.eq_ignore_ascii_case("INFLUX") {
+                Command::Format(OutputFormat::Influx)
+            } else if cfg!(feature = "binary") && argument.eq_ignore_ascii_case("BINARY") {
+                Command::Format(OutputFormat::Binary)
+            } else if cfg!(feature = "nmea") && argument.eq_ignore_ascii_case("NMEA") {
+                Command::Format(OutputFormat::Nmea)
+            } else {
+                return Err(CommandError::InvalidArgument);
+            }
+        } else if cfg!(feature = "modbus") && name.eq_ignore_ascii_case("MODBUS") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let address = argument
+                .parse()
+                .map_err(|_| CommandError::InvalidArgument)?;
+
+            if !(1..=MAX_SLAVE_ADDRESS).contains(&address) {
+                return Err(CommandError::InvalidArgument);
+            }
+
+            Command::Modbus(address)
+        } else if cfg!(feature = "forecast") && name.eq_ignore_ascii_case("ALTITUDE") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let altitude = argument.parse().map_err(|_| CommandError::InvalidArgument)?;
+
+            if !(MIN_ALTITUDE..=MAX_ALTITUDE).contains(&altitude) {
+                return Err(CommandError::InvalidArgument);
+            }
+
+            Command::Altitude(altitude)
+        } else if cfg!(feature = "alarms") && name.eq_ignore_ascii_case("ALARM") {
+            match tokens.next() {
+                Some(quantity) => {
+                    let kind = if quantity.eq_ignore_ascii_case("STORM") {
+                        AlarmKind::PressureDrop
+                    } else {
+                        let limit = tokens.next().ok_or(CommandError::MissingArgument)?;
+                        let high = if limit.eq_ignore_ascii_case("HIGH") {
+                            true
+                        } else if limit.eq_ignore_ascii_case("LOW") {
+                            false
+                        } else {
+                            return Err(CommandError::InvalidArgument);
+                        };
+
+                        if quantity.eq_ignore_ascii_case("T") {
+                            if high {
+                                AlarmKind::TemperatureHigh
+                            } else {
+                                AlarmKind::TemperatureLow
+                            }
+                        } else if quantity.eq_ignore_ascii_case("H") {
+                            if high {
+                                AlarmKind::HumidityHigh
+                            } else {
+                                AlarmKind::HumidityLow
+                            }
+                        } else {
+                            return Err(CommandError::InvalidArgument);
+                        }
+                    };
+                    let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+                    let threshold = if argument.eq_ignore_ascii_case("OFF") {
+                        None
+                    } else {
+                        let (min, max) = kind.range();
+                        let threshold = parse_hundredths(argument).ok_or(CommandError::InvalidArgument)?;
+
+                        if !(min..=max).contains(&threshold) {
+                            return Err(CommandError::InvalidArgument);
+                        }
+
+                        Some(threshold)
+                    };
+
+                    Command::Alarm(kind, threshold)
+                }
+                None => Command::Alarms,
+            }
+        } else if name.eq_ignore_ascii_case("BAUD") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+            let baud_rate = argument
+                .parse()
+                .map_err(|_| CommandError::InvalidArgument)?;
+
+            if !BAUD_RATES.contains(&baud_rate) {
+                return Err(CommandError::InvalidArgument);
+            }
+
+            Command::Baud(baud_rate)
+        } else if cfg!(feature = "bluetooth") && name.eq_ignore_ascii_case("BLUETOOTH") {
+            let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+
+            if argument.eq_ignore_ascii_case("NAME") {
+                let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+
+                Command::BluetoothName(Name::parse(argument).ok_or(CommandError::InvalidArgument)?)
+            } else if argument.eq_ignore_ascii_case("PIN") {
+                let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+
+                Command::BluetoothPin(Pin::parse(argument).ok_or(CommandError::InvalidArgument)?)
+            } else if argument.eq_ignore_ascii_case("HC05") {
+                Command::Bluetooth(BluetoothModule::Hc05)
+            } else if argument.eq_ignore_ascii_case("HC06") {
+                Command::Bluetooth(BluetoothModule::Hc06)
+            } else if argument.eq_ignore_ascii_case("HM10") {
+                Command::Bluetooth(BluetoothModule::Hm10)
+            } else if argument.eq_ignore_ascii_case("NONE") {
+                Command::Bluetooth(BluetoothModule::None)
+            } else {
+                return Err(CommandError::InvalidArgument);
+            }
+        } else if name.eq_ignore_ascii_case("HISTORY") {
+            match tokens.next() {
+                Some(argument) if argument.eq_ignore_ascii_case("CLEAR") => Command::ClearHistory,
+                Some(_) => return Err(CommandError::InvalidArgument),
+                None => Command::History,
+            }
+        } else if cfg!(feature = "statistics") && name.eq_ignore_ascii_case("STATS") {
+            match tokens.next() {
+                Some(argument) if argument.eq_ignore_ascii_case("CLEAR") => Command::ClearStatistics,
+                Some(argument) if argument.eq_ignore_ascii_case("WINDOWS") => {
+                    let mut windows = [0; 2];
+
+                    for window in windows.iter_mut() {
+                        let argument = tokens.next().ok_or(CommandError::MissingArgument)?;
+                        *window = argument
+                            .parse()
+                            .map_err(|_| CommandError::InvalidArgument)?;
+
+                        if !(1..=MAX_WINDOW).contains(window) {
+                            return Err(CommandError::InvalidArgument);
+                        }
+                    }
+
+                    Command::StatisticsWindows(windows[0], windows[1])
+                }
+                Some(_) => return Err(CommandError::InvalidArgument),
+                None => Command::Statistics,
+            }
+        } else if name.eq_ignore_ascii_case("TIME") {
+            match tokens.next() {
+                Some(argument) => {
+                    Command::Time(Some(DateTime::parse(argument).ok_or(CommandError::InvalidArgument)?))
+                }
+                None => Command::Time(None),
+            }
+        } else if name.eq_ignore_ascii_case("RESET") {
+            Command::Reset
+        } else if name.eq_ignore_ascii_case("INFO") {
+            Command::Info
+        } else {
+            return Err(CommandError::UnknownCommand);
+        };
+
+        if tokens.next().is_some() {
+            return Err(CommandError::TooManyArguments);
+        }
+
+        Ok(command)
+    }
+}
+
+// Parses decimal number with up to 2 fractional digits to hundredths, for example `-2.5` to -250.
+fn parse_hundredths(value: &str) -> Option<i32> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+    if integer.is_empty()
+        || fraction.len() > 2
+        || !integer.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let integer: i32 = integer.parse().ok()?;
+    let fraction: i32 = match fraction.len() {
+        0 => 0,
+        1 => fraction.parse::<i32>().ok()? * 10,
+        _ => fraction.parse().ok()?,
+    };
+    let value = integer.checked_mul(100)?.checked_add(fraction)?;
+
+    Some(if negative { -value } else { value })
+}
+
+// Collects received bytes into lines, lines can be terminated by `\r`, `\n` or both. Empty lines are ignored.
+#[derive(Debug)]
+pub struct CommandReader {
+    buffer: [u8; LINE_SIZE],
+    length: usize,
+    overflow: bool,
+}
+
+impl CommandReader {
+    pub const fn new() -> CommandReader {
+        CommandReader {
+            buffer: [0; LINE_SIZE],
+            length: 0,
+            overflow: false,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) -> Option<Result<Command, CommandError>> {
+        match byte {
+            b'\r' | b'\n' => {
+                let length = self.length;
+                let overflow = self.overflow;
+                self.length = 0;
+                self.overflow = false;
+
+                if overflow {
+                    Some(Err(CommandError::LineTooLong))
+                } else if length == 0 {
+                    None
+                } else {
+                    match from_utf8(&self.buffer[..length]) {
+                        Ok(line) => Some(Command::parse(line)),
+                        Err(_) => Some(Err(CommandError::UnknownCommand)),
+                    }
+                }
+            }
+            _ if self.length < LINE_SIZE => {
+                self.buffer[self.length] = byte;
+                self.length += 1;
+
+                None
+            }
+            _ => {
+                self.overflow = true;
+
+                None
+            }
+        }
+    }
+}
+
+impl Default for CommandReader {
+    fn default() -> CommandReader {
+        CommandReader::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(reader: &mut CommandReader, bytes: &[u8]) -> Option<Result<Command, CommandError>> {
+        let mut result = None;
+
+        for byte in bytes {
+            if let Some(command) = reader.push(*byte) {
+                assert!(result.is_none(), "one command per line");
+                result = Some(command);
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(Command::parse("READ"), Ok(Command::Read));
+        assert_eq!(Command::parse("  interval   300 "), Ok(Command::Interval(300)));
+        assert_eq!(
+            Command::parse("UNITS f hPa"),
+            Ok(Command::Units(Some(TemperatureUnit::Fahrenheit), Some(PressureUnit::Hectopascal)))
+        );
+        assert_eq!(
+            Command::parse("OVERSAMPLE T2 h0"),
+            Ok(Command::Oversample(
+                Some(TemperatireSampling::Sampling2),
+                None,
+                Some(HumiditySampling::Skipped)
+            ))
+        );
+        assert_eq!(Command::parse("SMOOTH 5 20"), Ok(Command::Smooth(5, 20)));
+        assert_eq!(Command::parse("FORMAT nmea"), Ok(Command::Format(OutputFormat::Nmea)));
+        assert_eq!(Command::parse("MODBUS 247"), Ok(Command::Modbus(247)));
+        assert_eq!(Command::parse("ALTITUDE -500"), Ok(Command::Altitude(-500)));
+        assert_eq!(
+            Command::parse("ALARM T LOW -2.5"),
+            Ok(Command::Alarm(AlarmKind::TemperatureLow, Some(-250)))
+        );
+        assert_eq!(
+            Command::parse("ALARM STORM OFF"),
+            Ok(Command::Alarm(AlarmKind::PressureDrop, None))
+        );
+        assert_eq!(Command::parse("ALARM"), Ok(Command::Alarms));
+        assert_eq!(Command::parse("BAUD 38400"), Ok(Command::Baud(38_400)));
+        assert_eq!(Command::parse("BLUETOOTH hm10"), Ok(Command::Bluetooth(BluetoothModule::Hm10)));
+        assert_eq!(
+            Command::parse("BLUETOOTH NAME bme-280_1"),
+            Ok(Command::BluetoothName(Name::parse("bme-280_1").unwrap()))
+        );
+        assert_eq!(
+            Command::parse("BLUETOOTH PIN 0042"),
+            Ok(Command::BluetoothPin(Pin::from_u32(4 << 24 | 42).unwrap()))
+        );
+        assert_eq!(Command::parse("HISTORY CLEAR"), Ok(Command::ClearHistory));
+        assert_eq!(Command::parse("STATS"), Ok(Command::Statistics));
+        assert_eq!(Command::parse("stats windows 3 48"), Ok(Command::StatisticsWindows(3, 48)));
+        assert_eq!(
+            Command::parse("TIME 2026-10-19T12:34:56"),
+            Ok(Command::Time(DateTime::parse("2026-10-19T12:34:56")))
+        );
+        assert_eq!(Command::parse("TIME"), Ok(Command::Time(None)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Command::parse(""), Err(CommandError::UnknownCommand));
+        assert_eq!(Command::parse("MEASURE"), Err(CommandError::UnknownCommand));
+        assert_eq!(Command::parse("INTERVAL"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("UNITS"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("SMOOTH 5"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("ALARM H HIGH"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("UNITS K"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("OVERSAMPLE T3"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("FILTER 3"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("POWER OFF"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("BAUD 14400"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("BLUETOOTH PIN 12345"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("BLUETOOTH NAME bme280.1"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("TIME 2026-10-19"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("STATS WINDOWS 1"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("STATS WINDOWS 1 24 168"), Err(CommandError::TooManyArguments));
+        assert_eq!(Command::parse("READ NOW"), Err(CommandError::TooManyArguments));
+        assert_eq!(Command::parse("INTERVAL 60 S"), Err(CommandError::TooManyArguments));
+    }
+
+    #[test]
+    fn limits() {
+        assert_eq!(Command::parse("INTERVAL 86400"), Ok(Command::Interval(MAX_INTERVAL)));
+        assert_eq!(Command::parse("INTERVAL 0"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("INTERVAL 86401"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("SMOOTH 8 100"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("SMOOTH 1 0"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("MODBUS 0"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("MODBUS 248"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("ALTITUDE 9001"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("STATS WINDOWS 1 168"), Ok(Command::StatisticsWindows(1, MAX_WINDOW)));
+        assert_eq!(Command::parse("STATS WINDOWS 0 24"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("STATS WINDOWS 1 169"), Err(CommandError::InvalidArgument));
+        assert_eq!(
+            Command::parse("ALARM H HIGH 100"),
+            Ok(Command::Alarm(AlarmKind::HumidityHigh, Some(10_000)))
+        );
+        assert_eq!(Command::parse("ALARM H HIGH 100.01"), Err(CommandError::InvalidArgument));
+        assert_eq!(Command::parse("ALARM STORM 0.05"), Err(CommandError::InvalidArgument));
+    }
+
+    #[test]
+    fn malformed_numbers() {
+        for line in [
+            "INTERVAL 1x",
+            "INTERVAL -60",
+            "INTERVAL 4294967296",
+            "INTERVAL 6e1",
+            "OVERSAMPLE T",
+            "OVERSAMPLE T-1",
+            "SMOOTH 5 2.5",
+            "MODBUS 0x10",
+            "ALTITUDE 1,000",
+            "STATS WINDOWS 1h 24h",
+            "ALARM T HIGH 25,5",
+            "ALARM T HIGH 25.555",
+            "ALARM T HIGH .5",
+            "ALARM T HIGH --5",
+            "ALARM T HIGH 2.-5",
+            "ALARM T HIGH +25",
+        ] {
+            assert_eq!(Command::parse(line), Err(CommandError::InvalidArgument), "{}", line);
+        }
+    }
+
+    #[test]
+    fn hundredths() {
+        assert_eq!(parse_hundredths("25"), Some(2_500));
+        assert_eq!(parse_hundredths("25.5"), Some(2_550));
+        assert_eq!(parse_hundredths("25.05"), Some(2_505));
+        assert_eq!(parse_hundredths("-0.01"), Some(-1));
+        assert_eq!(parse_hundredths("007"), Some(700));
+
+        assert_eq!(parse_hundredths(""), None);
+        assert_eq!(parse_hundredths("-"), None);
+        assert_eq!(parse_hundredths("-.5"), None);
+        assert_eq!(parse_hundredths("1.234"), None);
+        assert_eq!(parse_hundredths("1.2.3"), None);
+        assert_eq!(parse_hundredths("21474837"), None);
+        assert_eq!(parse_hundredths("²5"), None);
+    }
+
+    #[test]
+    fn lines() {
+        let mut reader = CommandReader::new();
+
+        assert_eq!(read(&mut reader, b"READ"), None);
+        assert_eq!(read(&mut reader, b"\r\n"), Some(Ok(Command::Read)));
+        assert_eq!(read(&mut reader, b"\n\r\n"), None);
+        assert_eq!(read(&mut reader, b"INTERVAL 60\r"), Some(Ok(Command::Interval(60))));
+        assert_eq!(read(&mut reader, b"\nINFO\n"), Some(Ok(Command::Info)));
+        assert_eq!(read(&mut reader, b"RE\xFFAD\n"), Some(Err(CommandError::UnknownCommand)));
+        assert_eq!(read(&mut reader, b"READ\n"), Some(Ok(Command::Read)));
+    }
+
+    #[test]
+    fn long_lines() {
+        let mut reader = CommandReader::new();
+        let mut line = [b' '; LINE_SIZE + 1];
+        line[..4].copy_from_slice(b"READ");
+
+        // Line of `LINE_SIZE` bytes fits into buffer, trailing spaces are ignored.
+        assert_eq!(read(&mut reader, &line[..LINE_SIZE]), None);
+        assert_eq!(read(&mut reader, b"\n"), Some(Ok(Command::Read)));
+
+        assert_eq!(read(&mut reader, &line), None);
+        assert_eq!(read(&mut reader, b"\n"), Some(Err(CommandError::LineTooLong)));
+
+        // Overflow is reported once and next line is read from start.
+        assert_eq!(read(&mut reader, b"INFO\n"), Some(Ok(Command::Info)));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::crc::crc16;
+
+// Frame layout, multi-byte values are little-endian:
+//
+// | Offset | Size | Field                                                     |
+// |--------|------|-----------------------------------------------------------|
+// | 0      | 2    | Sync bytes `0xAA 0x55`                                    |
+// | 2      | 1    | Protocol version                                          |
+// | 3      | 12   | Device ID                                                 |
+// | 15     | 4    | Sequence number                                           |
+// | 19     | 4    | Time, Unix seconds if clock is set                        |
+// | 23     | 4    | Temperature, signed, 0.01 C                               |
+// | 27     | 4    | Pressure, Pa in Q24.8 fixed point                         |
+// | 31     | 4    | Humidity, % in Q22.10 fixed point                         |
+// | 35     | 1    | Status flags, see `Flags`                                 |
+// | 36     | 2    | CRC-16/CCITT-FALSE of bytes from version to status flags  |
+pub const FRAME_SIZE: usize = 38;
+
+const SYNC: [u8; 2] = [0xAA, 0x55];
+const VERSION: u8 = 2; // Version 2 added `REJECTED` flag, older decoders reject new frames.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    Length,
+    Sync,
+    Version,
+    Crc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags(pub u8);
+
+impl Flags {
+    pub const CLOCK_SET: u8 = 0b0000_0001;
+    pub const SKIPPED: u8 = 0b0000_0010; // Value not measured this time (oversampling 0 or skipped by sensor).
+    pub const CLAMPED: u8 = 0b0000_0100; // Humidity clamped to 0 or 100% by compensation.
+    pub const OUT_OF_RANGE: u8 = 0b0000_1000; // Value outside of operating range of BME280.
+    pub const STALE: u8 = 0b0001_0000; // Forced measurement did not start new conversion.
+    pub const RECOVERED: u8 = 0b0010_0000; // First measurement after I2C bus recovery.
+    pub const REJECTED: u8 = 0b0100_0000; // Value rejected as spike, previous one is repeated.
+
+    const NAMES: [(u8, &'static str); 7] = [
+        (Flags::CLOCK_SET, "clock_set"),
+        (Flags::SKIPPED, "skipped"),
+        (Flags::CLAMPED, "clamped"),
+        (Flags::OUT_OF_RANGE, "out_of_range"),
+        (Flags::STALE, "stale"),
+        (Flags::RECOVERED, "recovered"),
+        (Flags::REJECTED, "rejected"),
+    ];
+
+    pub fn contains(&self, flag: u8) -> bool {
+        self.0 & flag == flag
+    }
+
+    pub fn insert(&mut self, flag: u8) {
+        self.0 |= flag;
+    }
+
+    // Flag with given name, see `names`.
+    pub fn from_name(name: &str) -> Option<u8> {
+        Flags::NAMES
+            .into_iter()
+            .find(|(_, flag_name)| *flag_name == name)
+            .map(|(flag, _)| flag)
+    }
+
+    // Names of set flags, unknown bits are skipped.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        let flags = *self;
+
+        Flags::NAMES
+            .into_iter()
+            .filter(move |(flag, _)| flags.contains(*flag))
+            .map(|(_, name)| name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub device_id: [u8; 12],
+    pub sequence: u32,
+    pub time: u32,
+    pub temperature: i32,
+    pub pressure: u32,
+    pub humidity: u32,
+    pub flags: Flags,
+}
+
+impl Frame {
+    pub fn encode(&self) -> [u8; FRAME_SIZE] {
+        let mut buffer = [0; FRAME_SIZE];
+        buffer[0..2].copy_from_slice(&SYNC);
+        buffer[2] = VERSION;
+        buffer[3..15].copy_from_slice(&self.device_id);
+        buffer[15..19].copy_from_slice(&self.sequence.to_le_bytes());
+        buffer[19..23].copy_from_slice(&self.time.to_le_bytes());
+        buffer[23..27].copy_from_slice(&self.temperature.to_le_bytes());
+        buffer[27..31].copy_from_slice(&self.pressure.to_le_bytes());
+        buffer[31..35].copy_from_slice(&self.humidity.to_le_bytes());
+        buffer[35] = self.flags.0;
+
+        let crc = crc16(&buffer[2..36]);
+        buffer[36..].copy_from_slice(&crc.to_le_bytes());
+
+        buffer
+    }
+
+    pub fn decode(buffer: &[u8]) -> Result<Frame, DecodeError> {
+        if buffer.len() != FRAME_SIZE {
+            return Err(DecodeError::Length);
+        }
+
+        if buffer[0..2] != SYNC {
+            return Err(DecodeError::Sync);
+        }
+
+        if buffer[36..] != crc16(&buffer[2..36]).to_le_bytes() {
+            return Err(DecodeError::Crc);
+        }
+
+        if buffer[2] != VERSION {
+            return Err(DecodeError::Version);
+        }
+
+        let mut device_id = [0; 12];
+        device_id.copy_from_slice(&buffer[3..15]);
+
+        Ok(Frame {
+            device_id,
+            sequence: u32::from_le_bytes([buffer[15], buffer[16], buffer[17], buffer[18]]),
+            time: u32::from_le_bytes([buffer[19], buffer[20], buffer[21], buffer[22]]),
+            temperature: i32::from_le_bytes([buffer[23], buffer[24], buffer[25], buffer[26]]),
+            pressure: u32::from_le_bytes([buffer[27], buffer[28], buffer[29], buffer[30]]),
+            humidity: u32::from_le_bytes([buffer[31], buffer[32], buffer[33], buffer[34]]),
+            flags: Flags(buffer[35]),
+        })
+    }
+}
+
+// Finds frames in byte stream, bytes before sync bytes (for example text replies to commands) are skipped.
+#[derive(Debug)]
+pub struct Decoder {
+    buffer: [u8; FRAME_SIZE],
+    length: usize,
+}
+
+impl Decoder {
+    pub const fn new() -> Decoder {
+        Decoder {
+            buffer: [0; FRAME_SIZE],
+            length: 0,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) -> Option<Result<Frame, DecodeError>> {
+        self.buffer[self.length] = byte;
+        self.length += 1;
+        self.skip_to_sync();
+
+        if self.length < FRAME_SIZE {
+            return None;
+        }
+
+        match Frame::decode(&self.buffer) {
+            Ok(frame) => {
+                self.length = 0;
+
+                Some(Ok(frame))
+            }
+            Err(error) => {
+                // Damaged frame, next frame may start inside of it.
+                self.drop_front(1);
+                self.skip_to_sync();
+
+                Some(Err(error))
+            }
+        }
+    }
+
+    // Bytes after sync bytes are buffered, so pushed bytes are part of frame rather than text.
+    pub fn in_frame(&self) -> bool {
+        self.length > 0
+    }
+
+    fn skip_to_sync(&mut self) {
+        while self.length > 0 && !SYNC.starts_with(&self.buffer[..self.length.min(SYNC.len())]) {
+            self.drop_front(1);
+        }
+    }
+
+    fn drop_front(&mut self, count: usize) {
+        self.buffer.copy_within(count..self.length, 0);
+        self.length -= count;
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Decoder {
+        Decoder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Frame = Frame {
+        device_id: [
+            0x20, 0x35, 0x47, 0x4B, 0x53, 0x56, 0x50, 0x0E, 0x00, 0x24, 0x00, 0x39,
+        ],
+        sequence: 42,
+        time: 1_792_413_296,
+        temperature: -1_234,
+        pressure: 24_674_867,
+        humidity: 47_445,
+        flags: Flags(Flags::CLOCK_SET),
+    };
+
+    fn decode_all(
+        decoder: &mut Decoder,
+        bytes: &[u8],
+        frames: &mut [Option<Frame>],
+        errors: &mut usize,
+    ) {
+        let mut index = 0;
+
+        for byte in bytes {
+            match decoder.push(*byte) {
+                Some(Ok(frame)) => {
+                    frames[index] = Some(frame);
+                    index += 1;
+                }
+                Some(Err(_)) => *errors += 1,
+                None => {}
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let buffer = FRAME.encode();
+
+        assert_eq!(buffer[..3], [0xAA, 0x55, 2]);
+        assert_eq!(Frame::decode(&buffer), Ok(FRAME));
+    }
+
+    #[test]
+    fn decode_errors() {
+        let buffer = FRAME.encode();
+        assert_eq!(Frame::decode(&buffer[1..]), Err(DecodeError::Length));
+
+        let mut damaged = buffer;
+        damaged[0] = 0;
+        assert_eq!(Frame::decode(&damaged), Err(DecodeError::Sync));
+
+        let mut damaged = buffer;
+        damaged[20] ^= 0x01;
+        assert_eq!(Frame::decode(&damaged), Err(DecodeError::Crc));
+
+        let mut damaged = buffer;
+        damaged[2] = VERSION + 1;
+        let crc = crc16(&damaged[2..36]);
+        damaged[36..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(Frame::decode(&damaged), Err(DecodeError::Version));
+    }
+
+    #[test]
+    fn flags() {
+        assert!(Flags(Flags::CLOCK_SET).contains(Flags::CLOCK_SET));
+        assert!(!Flags::default().contains(Flags::CLOCK_SET));
+
+        let mut flags = Flags::default();
+        flags.insert(Flags::STALE);
+        flags.insert(Flags::CLOCK_SET);
+        flags.insert(0b1000_0000);
+        assert!(flags.contains(Flags::STALE | Flags::CLOCK_SET));
+        assert!(!flags.contains(Flags::STALE | Flags::RECOVERED));
+
+        let mut names = flags.names();
+        assert_eq!(names.next(), Some("clock_set"));
+        assert_eq!(names.next(), Some("stale"));
+        assert_eq!(names.next(), None);
+
+        assert_eq!(Flags::from_name("recovered"), Some(Flags::RECOVERED));
+        assert_eq!(Flags::from_name("rejected"), Some(Flags::REJECTED));
+        assert_eq!(Flags::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn stream_with_text_and_damaged_frame() {
+        let frame = FRAME.encode();
+        let mut damaged = frame;
+        damaged[30] ^= 0xFF;
+        let second = Frame {
+            sequence: 43,
+            ..FRAME
+        };
+
+        let mut stream = [0; 8 + 3 * FRAME_SIZE];
+        stream[..4].copy_from_slice(b"OK.\n");
+        stream[4..4 + FRAME_SIZE].copy_from_slice(&frame);
+        stream[4 + FRAME_SIZE..4 + 2 * FRAME_SIZE].copy_from_slice(&damaged);
+        stream[4 + 2 * FRAME_SIZE..8 + 2 * FRAME_SIZE].copy_from_slice(&[0xAA, 0xAA, 0x55, 0x00]);
+        stream[8 + 2 * FRAME_SIZE..].copy_from_slice(&second.encode());
+
+        let mut decoder = Decoder::new();
+        let mut frames = [None; 3];
+        let mut errors = 0;
+        decode_all(&mut decoder, &stream[..4], &mut frames, &mut errors);
+        assert!(!decoder.in_frame());
+        decode_all(&mut decoder, &stream[4..10], &mut frames, &mut errors);
+        assert!(decoder.in_frame());
+        decode_all(&mut decoder, &stream[10..], &mut frames, &mut errors);
+
+        assert_eq!(frames, [Some(FRAME), Some(second), None]);
+        assert!(errors >= 1);
+    }
+}
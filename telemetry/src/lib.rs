@@ -0,0 +1,20 @@
+#![no_std]
+
+pub mod alarm;
+pub mod bluetooth;
+pub mod command;
+mod crc;
+mod frame;
+pub mod modbus;
+mod nmea;
+mod time;
+
+pub use crate::crc::crc16;
+pub use crate::crc::crc16_modbus;
+pub use crate::frame::DecodeError;
+pub use crate::frame::Decoder;
+pub use crate::frame::Flags;
+pub use crate::frame::Frame;
+pub use crate::frame::FRAME_SIZE;
+pub use crate::nmea::Sentence;
+pub use crate::time::DateTime;
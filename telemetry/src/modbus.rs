@@ -0,0 +1,470 @@
+use crate::crc::crc16_modbus;
+
+// Modbus RTU application data unit: slave address, function code, data and CRC.
+pub const MAX_FRAME_SIZE: usize = 256;
+pub const BROADCAST_ADDRESS: u8 = 0;
+pub const MAX_SLAVE_ADDRESS: u8 = 247;
+
+const READ_HOLDING_REGISTERS: u8 = 0x03;
+const READ_INPUT_REGISTERS: u8 = 0x04;
+const WRITE_SINGLE_REGISTER: u8 = 0x06;
+const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+const MAX_READ_COUNT: u16 = 125;
+const MAX_WRITE_COUNT: u16 = 123;
+const MIN_FRAME_SIZE: usize = 4;
+const FAST_FRAME_GAP_US: u32 = 1_750;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Exception {
+    IllegalFunction = 0x01,
+    IllegalDataAddress = 0x02,
+    IllegalDataValue = 0x03,
+    ServerDeviceFailure = 0x04,
+}
+
+pub trait Registers {
+    fn input(&self, address: u16) -> Option<u16>;
+
+    fn holding(&self, address: u16) -> Option<u16>;
+
+    fn set_holding(&mut self, address: u16, value: u16) -> Result<(), Exception>;
+}
+
+// Silent interval of 3.5 characters (11 bits each) ending a frame, fixed to 1.75 ms above 19200 baud.
+pub fn frame_gap_us(baud_rate: u32) -> u32 {
+    if baud_rate > 19_200 {
+        FAST_FRAME_GAP_US
+    } else {
+        38_500_000 / baud_rate
+    }
+}
+
+// Collects bytes received between silent intervals.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameBuffer {
+    data: [u8; MAX_FRAME_SIZE],
+    length: usize,
+    overflow: bool,
+}
+
+impl FrameBuffer {
+    pub const fn new() -> FrameBuffer {
+        FrameBuffer {
+            data: [0; MAX_FRAME_SIZE],
+            length: 0,
+            overflow: false,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) {
+        if self.length < MAX_FRAME_SIZE {
+            self.data[self.length] = byte;
+            self.length += 1;
+        } else {
+            self.overflow = true;
+        }
+    }
+
+    // Returns received frame, frames longer than `MAX_FRAME_SIZE` are dropped.
+    pub fn frame(&self) -> Option<&[u8]> {
+        if self.overflow || self.length == 0 {
+            None
+        } else {
+            Some(&self.data[..self.length])
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.length = 0;
+        self.overflow = false;
+    }
+}
+
+impl Default for FrameBuffer {
+    fn default() -> FrameBuffer {
+        FrameBuffer::new()
+    }
+}
+
+// Processes request and writes response, returns response length. Frames with invalid CRC, frames for other slaves
+// and broadcast requests are not answered. Registers are written only if all values of request are accepted.
+pub fn process<R>(
+    address: u8,
+    request: &[u8],
+    registers: &mut R,
+    response: &mut [u8; MAX_FRAME_SIZE],
+) -> Option<usize>
+where
+    R: Registers + Clone,
+{
+    if request.len() < MIN_FRAME_SIZE {
+        return None;
+    }
+
+    let (pdu, crc) = request.split_at(request.len() - 2);
+
+    if crc != crc16_modbus(pdu).to_le_bytes() {
+        return None;
+    }
+
+    if pdu[0] != address && pdu[0] != BROADCAST_ADDRESS {
+        return None;
+    }
+
+    let function = pdu[1];
+    response[0] = address;
+    response[1] = function;
+
+    let length = match execute(function, &pdu[2..], registers, &mut response[2..]) {
+        Ok(length) => 2 + length,
+        Err(exception) => {
+            response[1] = function | 0x80;
+            response[2] = exception as u8;
+
+            3
+        }
+    };
+
+    if pdu[0] == BROADCAST_ADDRESS {
+        return None;
+    }
+
+    let crc = crc16_modbus(&response[..length]);
+    response[length..length + 2].copy_from_slice(&crc.to_le_bytes());
+
+    Some(length + 2)
+}
+
+// Executes function, writes response data after function code and returns its length.
+fn execute<R>(
+    function: u8,
+    data: &[u8],
+    registers: &mut R,
+    response: &mut [u8],
+) -> Result<usize, Exception>
+where
+    R: Registers + Clone,
+{
+    match function {
+        READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS => {
+            if data.len() != 4 {
+                return Err(Exception::IllegalDataValue);
+            }
+
+            let start = read_u16(data, 0);
+            let count = read_u16(data, 2);
+
+            if !(1..=MAX_READ_COUNT).contains(&count) {
+                return Err(Exception::IllegalDataValue);
+            }
+
+            response[0] = (count * 2) as u8;
+
+            for index in 0..count {
+                let address = start
+                    .checked_add(index)
+                    .ok_or(Exception::IllegalDataAddress)?;
+                let value = if function == READ_HOLDING_REGISTERS {
+                    registers.holding(address)
+                } else {
+                    registers.input(address)
+                };
+                let offset = 1 + index as usize * 2;
+                response[offset..offset + 2]
+                    .copy_from_slice(&value.ok_or(Exception::IllegalDataAddress)?.to_be_bytes());
+            }
+
+            Ok(1 + count as usize * 2)
+        }
+        WRITE_SINGLE_REGISTER => {
+            if data.len() != 4 {
+                return Err(Exception::IllegalDataValue);
+            }
+
+            let address = read_u16(data, 0);
+            registers
+                .holding(address)
+                .ok_or(Exception::IllegalDataAddress)?;
+            registers.set_holding(address, read_u16(data, 2))?;
+            response[..4].copy_from_slice(data);
+
+            Ok(4)
+        }
+        WRITE_MULTIPLE_REGISTERS => {
+            if data.len() < 5 {
+                return Err(Exception::IllegalDataValue);
+            }
+
+            let start = read_u16(data, 0);
+            let count = read_u16(data, 2);
+
+            if !(1..=MAX_WRITE_COUNT).contains(&count)
+                || data[4] as usize != count as usize * 2
+                || data.len() != 5 + count as usize * 2
+            {
+                return Err(Exception::IllegalDataValue);
+            }
+
+            for index in 0..count {
+                let address = start
+                    .checked_add(index)
+                    .ok_or(Exception::IllegalDataAddress)?;
+                registers
+                    .holding(address)
+                    .ok_or(Exception::IllegalDataAddress)?;
+            }
+
+            // Values are checked by writing copy, registers may depend on each other.
+            let mut updated = registers.clone();
+
+            for index in 0..count {
+                updated.set_holding(start + index, read_u16(data, 5 + index as usize * 2))?;
+            }
+
+            *registers = updated;
+
+            response[..4].copy_from_slice(&data[..4]);
+
+            Ok(4)
+        }
+        _ => Err(Exception::IllegalFunction),
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: u8 = 17;
+
+    #[derive(Clone)]
+    struct TestRegisters {
+        input: [u16; 4],
+        holding: [u16; 3],
+    }
+
+    impl Registers for TestRegisters {
+        fn input(&self, address: u16) -> Option<u16> {
+            self.input.get(address as usize).copied()
+        }
+
+        fn holding(&self, address: u16) -> Option<u16> {
+            self.holding.get(address as usize).copied()
+        }
+
+        fn set_holding(&mut self, address: u16, value: u16) -> Result<(), Exception> {
+            if value > 1_000 {
+                return Err(Exception::IllegalDataValue);
+            }
+
+            self.holding[address as usize] = value;
+
+            Ok(())
+        }
+    }
+
+    fn registers() -> TestRegisters {
+        TestRegisters {
+            input: [2_387, 9_639, 3_584, 0x0003],
+            holding: [60, 0, 2],
+        }
+    }
+
+    // Appends CRC to request and returns response without CRC, checks response CRC.
+    fn transact<'a>(
+        registers: &mut TestRegisters,
+        request: &[u8],
+        response: &'a mut [u8; MAX_FRAME_SIZE],
+    ) -> Option<&'a [u8]> {
+        let mut frame = [0; MAX_FRAME_SIZE];
+        frame[..request.len()].copy_from_slice(request);
+        let crc = crc16_modbus(request);
+        frame[request.len()..request.len() + 2].copy_from_slice(&crc.to_le_bytes());
+
+        let length = process(ADDRESS, &frame[..request.len() + 2], registers, response)?;
+        let (pdu, crc) = response[..length].split_at(length - 2);
+        assert_eq!(crc, crc16_modbus(pdu).to_le_bytes());
+
+        Some(pdu)
+    }
+
+    #[test]
+    fn known_frame_crc() {
+        // Read 3 holding registers from 107 of slave 17, example from Modbus specification.
+        let request = [0x11, 0x03, 0x00, 0x6B, 0x00, 0x03];
+
+        assert_eq!(crc16_modbus(&request).to_le_bytes(), [0x76, 0x87]);
+    }
+
+    #[test]
+    fn read_input_registers() {
+        let mut registers = registers();
+        let mut response = [0; MAX_FRAME_SIZE];
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS, 0x04, 0x00, 0x01, 0x00, 0x02],
+            &mut response,
+        );
+
+        assert_eq!(
+            pdu,
+            Some(&[ADDRESS, 0x04, 0x04, 0x25, 0xA7, 0x0E, 0x00][..])
+        );
+    }
+
+    #[test]
+    fn read_holding_registers() {
+        let mut registers = registers();
+        let mut response = [0; MAX_FRAME_SIZE];
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS, 0x03, 0x00, 0x00, 0x00, 0x03],
+            &mut response,
+        );
+
+        assert_eq!(
+            pdu,
+            Some(&[ADDRESS, 0x03, 0x06, 0x00, 0x3C, 0x00, 0x00, 0x00, 0x02][..])
+        );
+    }
+
+    #[test]
+    fn write_single_register() {
+        let mut registers = registers();
+        let mut response = [0; MAX_FRAME_SIZE];
+        let request = [ADDRESS, 0x06, 0x00, 0x00, 0x00, 0x1E];
+        let pdu = transact(&mut registers, &request, &mut response);
+
+        assert_eq!(pdu, Some(&request[..]));
+        assert_eq!(registers.holding[0], 30);
+    }
+
+    #[test]
+    fn write_multiple_registers() {
+        let mut registers = registers();
+        let mut response = [0; MAX_FRAME_SIZE];
+        let request = [
+            ADDRESS, 0x10, 0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x01, 0x00, 0x00,
+        ];
+        let pdu = transact(&mut registers, &request, &mut response);
+
+        assert_eq!(pdu, Some(&request[..6]));
+        assert_eq!(registers.holding, [60, 1, 0]);
+    }
+
+    #[test]
+    fn exceptions() {
+        let mut registers = registers();
+        let mut response = [0; MAX_FRAME_SIZE];
+
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS, 0x05, 0x00, 0x00, 0xFF, 0x00],
+            &mut response,
+        );
+        assert_eq!(pdu, Some(&[ADDRESS, 0x85, 0x01][..]));
+
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS, 0x04, 0x00, 0x03, 0x00, 0x02],
+            &mut response,
+        );
+        assert_eq!(pdu, Some(&[ADDRESS, 0x84, 0x02][..]));
+
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS, 0x03, 0x00, 0x00, 0x00, 0x00],
+            &mut response,
+        );
+        assert_eq!(pdu, Some(&[ADDRESS, 0x83, 0x03][..]));
+
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS, 0x06, 0x00, 0x01, 0x10, 0x00],
+            &mut response,
+        );
+        assert_eq!(pdu, Some(&[ADDRESS, 0x86, 0x03][..]));
+
+        // Registers are not written if any address is invalid.
+        let request = [
+            ADDRESS, 0x10, 0x00, 0x02, 0x00, 0x02, 0x04, 0x00, 0x01, 0x00, 0x01,
+        ];
+        let pdu = transact(&mut registers, &request, &mut response);
+        assert_eq!(pdu, Some(&[ADDRESS, 0x90, 0x02][..]));
+        assert_eq!(registers.holding, [60, 0, 2]);
+
+        // Registers are not written if any value is invalid.
+        let request = [
+            ADDRESS, 0x10, 0x00, 0x00, 0x00, 0x02, 0x04, 0x00, 0x1E, 0x10, 0x00,
+        ];
+        let pdu = transact(&mut registers, &request, &mut response);
+        assert_eq!(pdu, Some(&[ADDRESS, 0x90, 0x03][..]));
+        assert_eq!(registers.holding, [60, 0, 2]);
+    }
+
+    #[test]
+    fn ignored_frames() {
+        let mut registers = registers();
+        let mut response = [0; MAX_FRAME_SIZE];
+
+        // Other slave.
+        let pdu = transact(
+            &mut registers,
+            &[ADDRESS + 1, 0x03, 0x00, 0x00, 0x00, 0x01],
+            &mut response,
+        );
+        assert_eq!(pdu, None);
+
+        // Broadcast write is executed without response.
+        let pdu = transact(
+            &mut registers,
+            &[0x00, 0x06, 0x00, 0x02, 0x00, 0x01],
+            &mut response,
+        );
+        assert_eq!(pdu, None);
+        assert_eq!(registers.holding[2], 1);
+
+        // Damaged frame.
+        let mut frame = [ADDRESS, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00];
+        let crc = crc16_modbus(&frame[..6]) ^ 0x0001;
+        frame[6..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(
+            process(ADDRESS, &frame, &mut registers, &mut response),
+            None
+        );
+        assert_eq!(
+            process(ADDRESS, &frame[..3], &mut registers, &mut response),
+            None
+        );
+    }
+
+    #[test]
+    fn frame_gap() {
+        assert_eq!(frame_gap_us(9_600), 4_010);
+        assert_eq!(frame_gap_us(19_200), 2_005);
+        assert_eq!(frame_gap_us(115_200), 1_750);
+    }
+
+    #[test]
+    fn frame_buffer() {
+        let mut buffer = FrameBuffer::new();
+        assert_eq!(buffer.frame(), None);
+
+        buffer.push(0x11);
+        buffer.push(0x03);
+        assert_eq!(buffer.frame(), Some(&[0x11, 0x03][..]));
+
+        for _ in 0..MAX_FRAME_SIZE {
+            buffer.push(0x00);
+        }
+        assert_eq!(buffer.frame(), None);
+
+        buffer.clear();
+        assert_eq!(buffer.frame(), None);
+    }
+}
@@ -0,0 +1,99 @@
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+
+// Writes NMEA 0183 sentence `$<address>,<fields>*<checksum>\r\n`, checksum is XOR of characters between `$` and `*`.
+pub struct Sentence<'a, W>
+where
+    W: Write + ?Sized,
+{
+    writer: &'a mut W,
+    checksum: u8,
+}
+
+impl<'a, W> Sentence<'a, W>
+where
+    W: Write + ?Sized,
+{
+    // Starts sentence with address, for example `WIMDA`. Fields are written with leading commas.
+    pub fn start(writer: &'a mut W, address: &str) -> Result<Sentence<'a, W>, core::fmt::Error> {
+        writer.write_char('$')?;
+
+        let mut sentence = Sentence {
+            writer,
+            checksum: 0,
+        };
+        sentence.write_str(address)?;
+
+        Ok(sentence)
+    }
+
+    pub fn finish(self) -> FmtResult {
+        write!(self.writer, "*{:02X}\r\n", self.checksum)
+    }
+}
+
+impl<'a, W> Write for Sentence<'a, W>
+where
+    W: Write + ?Sized,
+{
+    fn write_str(&mut self, value: &str) -> FmtResult {
+        for byte in value.bytes() {
+            self.checksum ^= byte;
+        }
+
+        self.writer.write_str(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Buffer {
+        data: [u8; 96],
+        length: usize,
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, value: &str) -> FmtResult {
+            let end = self.length + value.len();
+            self.data
+                .get_mut(self.length..end)
+                .ok_or(core::fmt::Error)?
+                .copy_from_slice(value.as_bytes());
+            self.length = end;
+
+            Ok(())
+        }
+    }
+
+    fn sentence(address: &str, fields: &str) -> Buffer {
+        let mut buffer = Buffer {
+            data: [0; 96],
+            length: 0,
+        };
+        let mut sentence = Sentence::start(&mut buffer, address).unwrap();
+        write!(sentence, "{}", fields).unwrap();
+        sentence.finish().unwrap();
+
+        buffer
+    }
+
+    #[test]
+    fn checksum() {
+        let buffer = sentence("GPGLL", ",5057.970,N,00146.110,E,142451,A");
+        assert_eq!(
+            &buffer.data[..buffer.length],
+            b"$GPGLL,5057.970,N,00146.110,E,142451,A*27\r\n"
+        );
+
+        let buffer = sentence(
+            "GPGGA",
+            ",092750.000,5321.6802,N,00630.3372,W,1,8,1.03,61.7,M,55.2,M,,",
+        );
+        assert_eq!(
+            &buffer.data[..buffer.length],
+            &b"$GPGGA,092750.000,5321.6802,N,00630.3372,W,1,8,1.03,61.7,M,55.2,M,,*76\r\n"[..]
+        );
+    }
+}
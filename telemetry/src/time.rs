@@ -0,0 +1,222 @@
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+
+const SECONDS_PER_DAY: u32 = 86_400;
+const DAYS_BEFORE_EPOCH: u32 = 719_468; // Days from 0000-03-01 to 1970-01-01.
+const MIN_YEAR: u16 = 2020;
+const MAX_YEAR: u16 = 2099;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+impl DateTime {
+    // Parses ISO-8601 date and time `YYYY-MM-DDTHH:MM:SS`.
+    pub fn parse(value: &str) -> Option<DateTime> {
+        let bytes = value.as_bytes();
+
+        if bytes.len() != 19
+            || bytes[4] != b'-'
+            || bytes[7] != b'-'
+            || !bytes[10].eq_ignore_ascii_case(&b'T')
+            || bytes[13] != b':'
+            || bytes[16] != b':'
+            || ![0..4, 5..7, 8..10, 11..13, 14..16, 17..19]
+                .into_iter()
+                .all(|field| bytes[field].iter().all(u8::is_ascii_digit))
+        {
+            return None;
+        }
+
+        let date_time = DateTime {
+            year: number(&bytes[0..4]) as u16,
+            month: number(&bytes[5..7]) as u8,
+            day: number(&bytes[8..10]) as u8,
+            hours: number(&bytes[11..13]) as u8,
+            minutes: number(&bytes[14..16]) as u8,
+            seconds: number(&bytes[17..19]) as u8,
+        };
+
+        if (MIN_YEAR..=MAX_YEAR).contains(&date_time.year)
+            && (1..=12).contains(&date_time.month)
+            && (1..=days_in_month(date_time.year, date_time.month)).contains(&date_time.day)
+            && date_time.hours < 24
+            && date_time.minutes < 60
+            && date_time.seconds < 60
+        {
+            Some(date_time)
+        } else {
+            None
+        }
+    }
+
+    // Converts seconds from 1970-01-01 to date and time, see `civil_from_days` by Howard Hinnant.
+    pub fn from_timestamp(timestamp: u32) -> DateTime {
+        let days = timestamp / SECONDS_PER_DAY + DAYS_BEFORE_EPOCH;
+        let seconds = timestamp % SECONDS_PER_DAY;
+        let era = days / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        DateTime {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hours: (seconds / 3_600) as u8,
+            minutes: (seconds / 60 % 60) as u8,
+            seconds: (seconds % 60) as u8,
+        }
+    }
+
+    // Converts date and time to seconds from 1970-01-01, see `days_from_civil` by Howard Hinnant.
+    pub fn timestamp(&self) -> u32 {
+        let month = self.month as u32;
+        let year = self.year as u32 - if month <= 2 { 1 } else { 0 };
+        let era = year / 400;
+        let year_of_era = year - era * 400;
+        let month_index = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month_index + 2) / 5 + self.day as u32 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - DAYS_BEFORE_EPOCH;
+
+        days * SECONDS_PER_DAY
+            + self.hours as u32 * 3_600
+            + self.minutes as u32 * 60
+            + self.seconds as u32
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.year >= MIN_YEAR
+    }
+}
+
+// Year is written as `u32`, so firmware does not link formatting code of `u16`.
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year as u32, self.month, self.day, self.hours, self.minutes, self.seconds
+        )
+    }
+}
+
+// Value of ASCII digits checked by caller.
+fn number(digits: &[u8]) -> u32 {
+    digits
+        .iter()
+        .fold(0, |value, digit| value * 10 + (digit - b'0') as u32)
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    const DATE_TIME: DateTime = DateTime {
+        year: 2026,
+        month: 10,
+        day: 19,
+        hours: 12,
+        minutes: 34,
+        seconds: 56,
+    };
+
+    struct Buffer {
+        data: [u8; 32],
+        length: usize,
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, value: &str) -> FmtResult {
+            let end = self.length + value.len();
+            self.data
+                .get_mut(self.length..end)
+                .ok_or(core::fmt::Error)?
+                .copy_from_slice(value.as_bytes());
+            self.length = end;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(DateTime::parse("2026-10-19T12:34:56"), Some(DATE_TIME));
+        assert_eq!(DateTime::parse("2026-10-19t12:34:56"), Some(DATE_TIME));
+        assert_eq!(DateTime::parse("2024-02-29T00:00:00").map(|date_time| date_time.day), Some(29));
+
+        assert_eq!(DateTime::parse("2026-10-19 12:34:56"), None);
+        assert_eq!(DateTime::parse("2026-10-19T12:34"), None);
+        assert_eq!(DateTime::parse("2026-10-19T12:34:56Z"), None);
+        assert_eq!(DateTime::parse("2026-1a-19T12:34:56"), None);
+        assert_eq!(DateTime::parse("2026-10-+9T12:34:56"), None);
+        assert_eq!(DateTime::parse("2019-12-31T23:59:59"), None);
+        assert_eq!(DateTime::parse("2100-01-01T00:00:00"), None);
+        assert_eq!(DateTime::parse("2026-13-01T00:00:00"), None);
+        assert_eq!(DateTime::parse("2026-02-29T00:00:00"), None);
+        assert_eq!(DateTime::parse("2026-04-31T00:00:00"), None);
+        assert_eq!(DateTime::parse("2026-10-19T24:00:00"), None);
+        assert_eq!(DateTime::parse("2026-10-19T12:60:00"), None);
+        assert_eq!(DateTime::parse("2026-10-19T12:34:60"), None);
+        assert_eq!(DateTime::parse("2026-10-19T12:34:ü"), None);
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(DATE_TIME.timestamp(), 1_792_413_296);
+        assert_eq!(DateTime::from_timestamp(1_792_413_296), DATE_TIME);
+        assert_eq!(DateTime::from_timestamp(0).year, 1970);
+        assert!(!DateTime::from_timestamp(946_684_800).is_set());
+
+        // Each day from 2020 to 2100, which is not leap year.
+        for day in (1_577_836_800..4_107_542_400).step_by(86_399) {
+            assert_eq!(DateTime::from_timestamp(day).timestamp(), day);
+        }
+    }
+
+    #[test]
+    fn display() {
+        for (timestamp, expected) in [
+            (0, "1970-01-01T00:00:00"),
+            (951_782_400, "2000-02-29T00:00:00"),
+            (1_709_164_800, "2024-02-29T00:00:00"),
+            (1_792_413_296, "2026-10-19T12:34:56"),
+            (4_102_444_799, "2099-12-31T23:59:59"),
+        ] {
+            let mut buffer = Buffer {
+                data: [0; 32],
+                length: 0,
+            };
+            write!(buffer, "{}", DateTime::from_timestamp(timestamp)).unwrap();
+
+            assert_eq!(&buffer.data[..buffer.length], expected.as_bytes());
+        }
+    }
+}